@@ -19,6 +19,11 @@ struct Options {
     flag_example: Vec<String>,
     flag_test: Vec<String>,
     flag_bench: Vec<String>,
+    flag_bins: bool,
+    flag_examples: bool,
+    flag_tests: bool,
+    flag_benches: bool,
+    flag_all_targets: bool,
     arg_args: Vec<String>,
 }
 
@@ -35,6 +40,11 @@ Options:
     --example NAME               Benchmark only the specified example
     --test NAME                  Benchmark only the specified test target
     --bench NAME                 Benchmark only the specified bench target
+    --bins                       Benchmark all binaries
+    --examples                   Benchmark all examples
+    --tests                      Benchmark all targets that have `test = true` set
+    --benches                    Benchmark all targets that have `bench = true` set
+    --all-targets                Benchmark all targets
     --no-run                     Compile, but don't run benchmarks
     -p SPEC, --package SPEC ...  Package to run benchmarks for
     -j N, --jobs N               The number of jobs to run in parallel
@@ -69,6 +79,11 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
     let ops = ops::TestOptions {
         no_run: options.flag_no_run,
         no_fail_fast: false,
+        list: false,
+        exact: false,
+        // Benchmarks running side by side would only skew each other
+        test_jobs: Some(1),
+        report: None,
         compile_opts: ops::CompileOptions {
             config: config,
             jobs: options.flag_jobs,
@@ -81,9 +96,14 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
             mode: ops::CompileMode::Bench,
             filter: ops::CompileFilter::new(options.flag_lib,
                                             &options.flag_bin,
+                                            options.flag_bins,
                                             &options.flag_test,
+                                            options.flag_tests,
                                             &options.flag_example,
-                                            &options.flag_bench),
+                                            options.flag_examples,
+                                            &options.flag_bench,
+                                            options.flag_benches,
+                                            options.flag_all_targets),
             target_rustdoc_args: None,
             target_rustc_args: None,
         },
@@ -5,7 +5,7 @@ extern crate rustc_serialize;
 extern crate toml;
 #[macro_use] extern crate log;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
 use std::fs;
 use std::io;
@@ -13,8 +13,10 @@ use std::path::{PathBuf, Path};
 use std::process::Command;
 
 use cargo::{execute_main_without_stdin, handle_error, shell};
-use cargo::core::MultiShell;
-use cargo::util::{CliError, CliResult, lev_distance, Config};
+use cargo::util::{CargoResult, CliError, CliResult, human, lev_distance};
+use cargo::core::shell::Verbosity::{Verbose, Quiet};
+use cargo::util::Config;
+use cargo::util::config::ConfigValue;
 
 #[derive(RustcDecodable)]
 struct Flags {
@@ -43,6 +45,7 @@ Options:
 
 Some common cargo commands are:
     build       Compile the current project
+    check       Type-check the current project without producing binaries
     clean       Remove the target directory
     doc         Build this project's and its dependencies' documentation
     new         Create a new cargo project
@@ -64,15 +67,21 @@ fn main() {
 macro_rules! each_subcommand{ ($mac:ident) => ({
     $mac!(bench);
     $mac!(build);
+    $mac!(check);
     $mac!(clean);
+    $mac!(config);
     $mac!(doc);
     $mac!(fetch);
     $mac!(generate_lockfile);
     $mac!(git_checkout);
+    $mac!(git_gc);
     $mac!(help);
+    $mac!(info);
+    $mac!(init);
     $mac!(install);
     $mac!(locate_project);
     $mac!(login);
+    $mac!(logout);
     $mac!(new);
     $mac!(owner);
     $mac!(package);
@@ -103,9 +112,21 @@ fn execute(flags: Flags, config: &Config) -> CliResult<Option<()>> {
     init_git_transports(config);
 
     if flags.flag_list {
+        let aliases = try!(list_aliases(config));
+        let mut commands = list_commands().into_iter().map(|command| {
+            (command, None)
+        }).collect::<BTreeMap<_, _>>();
+        commands.extend(aliases.into_iter().map(|(alias, expansion)| {
+            (alias, Some(expansion.join(" ")))
+        }));
         println!("Installed Commands:");
-        for command in list_commands().into_iter() {
-            println!("    {}", command);
+        for (command, expansion) in commands.into_iter() {
+            match expansion {
+                Some(expansion) => {
+                    println!("    {:<20} alias: {}", command, expansion)
+                }
+                None => println!("    {}", command),
+            }
         };
         return Ok(None)
     }
@@ -142,6 +163,7 @@ fn execute(flags: Flags, config: &Config) -> CliResult<Option<()>> {
         // example.
         _ => env::args().collect(),
     };
+    let args = try!(expand_aliases(config, args));
 
     macro_rules! cmd{ ($name:ident) => (
         if args[1] == stringify!($name).replace("_", "-") {
@@ -157,12 +179,104 @@ fn execute(flags: Flags, config: &Config) -> CliResult<Option<()>> {
     ) }
     each_subcommand!(cmd);
 
-    execute_subcommand(&args[1], &args, &mut config.shell());
+    execute_subcommand(config, &args[1], &args);
     Ok(None)
 }
 
-fn find_closest(cmd: &str) -> Option<String> {
-    let cmds = list_commands();
+/// Replace the command in `args` with its definition if it names an alias
+/// from the `[alias]` table of the configuration.
+///
+/// Aliases may be defined in terms of other aliases, but not in terms of
+/// themselves, and may not shadow a built-in command.
+fn expand_aliases(config: &Config, mut args: Vec<String>)
+                  -> CargoResult<Vec<String>> {
+    let mut seen = Vec::new();
+    while args.len() > 1 {
+        let expansion = match try!(aliased_command(config, &args[1])) {
+            Some(expansion) => expansion,
+            None => break,
+        };
+        let alias = args[1].clone();
+        if is_builtin(&alias) {
+            return Err(human(format!("alias `{}` shadows the built-in \
+                                      `cargo {}` command, remove it from \
+                                      the `[alias]` table", alias, alias)))
+        }
+        if seen.contains(&alias) {
+            seen.push(alias.clone());
+            return Err(human(format!("alias `{}` is defined in terms of \
+                                      itself: {}", alias, seen.join(" -> "))))
+        }
+        if expansion.is_empty() {
+            return Err(human(format!("alias `{}` has an empty definition",
+                                     alias)))
+        }
+        seen.push(alias);
+
+        let mut expanded = vec![args[0].clone()];
+        expanded.extend(expansion.into_iter());
+        expanded.extend(args[2..].iter().cloned());
+        args = expanded;
+    }
+    Ok(args)
+}
+
+/// Look up the definition of `command` in the `[alias]` table, which may be
+/// either a whitespace-separated string or a list of arguments.
+fn aliased_command(config: &Config, command: &str)
+                   -> CargoResult<Option<Vec<String>>> {
+    let aliases = match try!(config.get_table("alias")) {
+        Some((aliases, _)) => aliases,
+        None => return Ok(None),
+    };
+    match aliases.get(command) {
+        Some(&ConfigValue::String(ref s, _)) => {
+            Ok(Some(s.split_whitespace().map(|s| s.to_string()).collect()))
+        }
+        Some(&ConfigValue::List(ref list, _)) => {
+            Ok(Some(list.iter().map(|&(ref s, _)| s.clone()).collect()))
+        }
+        Some(val) => {
+            config.expected("string or list", &format!("alias.{}", command),
+                            val.clone())
+        }
+        None => Ok(None),
+    }
+}
+
+/// All aliases defined in the configuration, along with their definitions.
+fn list_aliases(config: &Config) -> CargoResult<BTreeMap<String, Vec<String>>> {
+    let mut ret = BTreeMap::new();
+    let aliases = match try!(config.get_table("alias")) {
+        Some((aliases, _)) => aliases,
+        None => return Ok(ret),
+    };
+    for alias in aliases.keys() {
+        if let Some(expansion) = try!(aliased_command(config, alias)) {
+            ret.insert(alias.clone(), expansion);
+        }
+    }
+    Ok(ret)
+}
+
+fn is_builtin(cmd: &str) -> bool {
+    let mut found = false;
+    macro_rules! check{ ($name:ident) => ({
+        if cmd == stringify!($name).replace("_", "-") {
+            found = true;
+        }
+    }) }
+    each_subcommand!(check);
+    found
+}
+
+fn find_closest(config: &Config, cmd: &str) -> Option<String> {
+    let mut cmds = list_commands();
+    // A broken alias table is reported when an alias is actually used, not
+    // while looking for suggestions.
+    if let Ok(aliases) = list_aliases(config) {
+        cmds.extend(aliases.into_iter().map(|(alias, _)| alias));
+    }
     // Only consider candidates with a lev_distance of 3 or less so we don't
     // suggest out-of-the-blue options.
     let mut filtered = cmds.iter().map(|c| (lev_distance(&c, cmd), c))
@@ -177,19 +291,23 @@ fn find_closest(cmd: &str) -> Option<String> {
     }
 }
 
-fn execute_subcommand(cmd: &str, args: &[String], shell: &mut MultiShell) {
+fn execute_subcommand(config: &Config, cmd: &str, args: &[String]) {
     let command = match find_command(cmd) {
         Some(command) => command,
         None => {
-            let msg = match find_closest(cmd) {
+            let msg = match find_closest(config, cmd) {
                 Some(closest) => format!("No such subcommand\n\n\t\
                                           Did you mean `{}`?\n", closest),
                 None => "No such subcommand".to_string()
             };
-            return handle_error(CliError::new(&msg, 127), shell)
+            return handle_error(CliError::new(&msg, 127), &mut config.shell())
         }
     };
-    match Command::new(&command).args(&args[1..]).status() {
+    let mut cmd = Command::new(&command);
+    cmd.args(&args[1..]);
+    subcommand_env(&mut cmd, config);
+    let shell = &mut config.shell();
+    match cmd.status() {
         Ok(ref status) if status.success() => {}
         Ok(ref status) => {
             match status.code() {
@@ -210,6 +328,21 @@ fn execute_subcommand(cmd: &str, args: &[String], shell: &mut MultiShell) {
     }
 }
 
+/// Tells an external subcommand how it was invoked, so it can behave like a
+/// built-in one. `Config::for_subcommand` reads these back.
+fn subcommand_env(cmd: &mut Command, config: &Config) {
+    if let Ok(exe) = env::current_exe() {
+        cmd.env("CARGO", exe);
+    }
+    cmd.env("CARGO_HOME", config.home());
+
+    let shell = config.shell();
+    let verbosity = shell.get_verbose();
+    cmd.env("CARGO_TERM_VERBOSE", (verbosity == Verbose).to_string())
+       .env("CARGO_TERM_QUIET", (verbosity == Quiet).to_string())
+       .env("CARGO_TERM_COLOR", shell.color_config().to_string());
+}
+
 /// List all runnable commands. find_command should always succeed
 /// if given one of returned command.
 fn list_commands() -> BTreeSet<String> {
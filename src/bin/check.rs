@@ -0,0 +1,108 @@
+use std::env;
+
+use cargo::ops::CompileOptions;
+use cargo::ops;
+use cargo::util::important_paths::{find_root_manifest_for_wd};
+use cargo::util::{CliResult, CliError, Config};
+
+#[derive(RustcDecodable)]
+struct Options {
+    flag_package: Vec<String>,
+    flag_jobs: Option<u32>,
+    flag_features: Vec<String>,
+    flag_no_default_features: bool,
+    flag_target: Option<String>,
+    flag_manifest_path: Option<String>,
+    flag_verbose: bool,
+    flag_quiet: bool,
+    flag_color: Option<String>,
+    flag_release: bool,
+    flag_lib: bool,
+    flag_bin: Vec<String>,
+    flag_example: Vec<String>,
+    flag_test: Vec<String>,
+    flag_bench: Vec<String>,
+    flag_bins: bool,
+    flag_examples: bool,
+    flag_tests: bool,
+    flag_benches: bool,
+    flag_all_targets: bool,
+}
+
+pub const USAGE: &'static str = "
+Check a local package and all of its dependencies for errors
+
+Usage:
+    cargo check [options]
+
+Options:
+    -h, --help                   Print this message
+    -p SPEC, --package SPEC ...  Package to check
+    -j N, --jobs N               The number of jobs to run in parallel
+    --lib                        Check only this package's library
+    --bin NAME                   Check only the specified binary
+    --example NAME               Check only the specified example
+    --test NAME                  Check only the specified test target
+    --bench NAME                 Check only the specified benchmark target
+    --bins                       Check all binaries
+    --examples                   Check all examples
+    --tests                      Check all targets that have `test = true` set
+    --benches                    Check all targets that have `bench = true` set
+    --all-targets                Check all targets
+    --release                    Check artifacts in release mode
+    --features FEATURES          Space-separated list of features to also check
+    --no-default-features        Do not check the `default` feature
+    --target TRIPLE              Check for the target triple
+    --manifest-path PATH         Path to the manifest to check
+    -v, --verbose                Use verbose output
+    -q, --quiet                  No output printed to stdout
+    --color WHEN                 Coloring: auto, always, never
+
+If the --package argument is given, then SPEC is a package id specification
+which indicates which package should be checked. If it is not given, then the
+current package is checked. For more information on SPEC and its format, see
+the `cargo help pkgid` command.
+
+Checking a package runs the compiler over it and all of its dependencies but
+stops before code generation, so no binaries or libraries are produced. Build
+scripts and plugins are still compiled in full as they need to run on the host.
+Results are tracked separately from `cargo build`, so the two never invalidate
+each other's artifacts.
+";
+
+pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
+    debug!("executing; cmd=cargo-check; args={:?}",
+           env::args().collect::<Vec<_>>());
+    try!(config.shell().set_verbosity(options.flag_verbose, options.flag_quiet));
+    try!(config.shell().set_color_config(options.flag_color.as_ref().map(|s| &s[..])));
+
+    let root = try!(find_root_manifest_for_wd(options.flag_manifest_path, config.cwd()));
+
+    let opts = CompileOptions {
+        config: config,
+        jobs: options.flag_jobs,
+        target: options.flag_target.as_ref().map(|t| &t[..]),
+        features: &options.flag_features,
+        no_default_features: options.flag_no_default_features,
+        spec: &options.flag_package,
+        exec_engine: None,
+        mode: ops::CompileMode::Check,
+        release: options.flag_release,
+        filter: ops::CompileFilter::new(options.flag_lib,
+                                        &options.flag_bin,
+                                        options.flag_bins,
+                                        &options.flag_test,
+                                        options.flag_tests,
+                                        &options.flag_example,
+                                        options.flag_examples,
+                                        &options.flag_bench,
+                                        options.flag_benches,
+                                        options.flag_all_targets),
+        target_rustdoc_args: None,
+        target_rustc_args: None,
+    };
+
+    ops::compile(&root, &opts).map(|_| None).map_err(|err| {
+        CliError::from_boxed(err, 101)
+    })
+}
@@ -0,0 +1,65 @@
+use cargo::ops;
+use cargo::util::{CliResult, CliError, Config};
+use cargo::util::config::Location;
+
+#[derive(RustcDecodable)]
+struct Options {
+    cmd_get: bool,
+    cmd_set: bool,
+    cmd_list: bool,
+    arg_key: Option<String>,
+    arg_value: Option<String>,
+    flag_global: bool,
+    flag_verbose: bool,
+    flag_quiet: bool,
+    flag_color: Option<String>,
+}
+
+pub const USAGE: &'static str = "
+Inspect and modify Cargo's configuration
+
+Usage:
+    cargo config get [options] <key>
+    cargo config set [options] <key> <value>
+    cargo config list [options]
+    cargo config -h | --help
+
+Options:
+    -h, --help               Print this message
+    --global                 Set the value in the global configuration file in
+                             the Cargo home directory
+    -v, --verbose            Use verbose output
+    -q, --quiet              No output printed to stdout
+    --color WHEN             Coloring: auto, always, never
+
+`get` prints the value of a dotted key such as `build.jobs`, along with the
+file or environment variable it was defined in. Tables print every value in
+them.
+
+`list` prints every value from all configuration files which apply to the
+current directory, merged together. Registry tokens are shown as
+`<redacted>`.
+
+`set` writes a value to `.cargo/config` in the current directory, or to the
+global configuration with `--global`, keeping the rest of the file as it is.
+Values are read as TOML, such as `4`, `true` or `[\"a\", \"b\"]`, and anything
+else is stored as a string.
+";
+
+pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
+    try!(config.shell().set_verbosity(options.flag_verbose, options.flag_quiet));
+    try!(config.shell().set_color_config(options.flag_color.as_ref().map(|s| &s[..])));
+
+    let key = options.arg_key.unwrap_or(String::new());
+    let result = if options.cmd_get {
+        ops::config_get(config, &key)
+    } else if options.cmd_set {
+        let loc = if options.flag_global {Location::Global} else {Location::Project};
+        let value = options.arg_value.unwrap_or(String::new());
+        ops::config_set(config, loc, &key, &value)
+    } else {
+        ops::config_list(config)
+    };
+    try!(result.map_err(|e| CliError::from_boxed(e, 101)));
+    Ok(None)
+}
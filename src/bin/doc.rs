@@ -10,12 +10,18 @@ struct Options {
     flag_manifest_path: Option<String>,
     flag_no_default_features: bool,
     flag_no_deps: bool,
+    flag_document_private_items: bool,
     flag_open: bool,
     flag_verbose: bool,
     flag_release: bool,
     flag_quiet: bool,
     flag_color: Option<String>,
     flag_package: Vec<String>,
+    flag_bins: bool,
+    flag_examples: bool,
+    flag_tests: bool,
+    flag_benches: bool,
+    flag_all_targets: bool,
 }
 
 pub const USAGE: &'static str = "
@@ -29,6 +35,12 @@ Options:
     --open                       Opens the docs in a browser after the operation
     -p SPEC, --package SPEC ...  Package to document
     --no-deps                    Don't build documentation for dependencies
+    --document-private-items     Document private items of the package too
+    --bins                       Document all binaries
+    --examples                   Document all examples
+    --tests                      Document all targets that have `test = true` set
+    --benches                    Document all targets that have `bench = true` set
+    --all-targets                Document all targets
     -j N, --jobs N               The number of jobs to run in parallel
     --release                    Build artifacts in release mode, with optimizations
     --features FEATURES          Space-separated list of features to also build
@@ -40,7 +52,11 @@ Options:
     --color WHEN                 Coloring: auto, always, never
 
 By default the documentation for the local package and all dependencies is
-built. The output is all placed in `target/doc` in rustdoc's usual format.
+built. The output is all placed in `target/doc` in rustdoc's usual format,
+along with an `index.html` listing every crate which was documented. Binaries
+are documented alongside the library, and one which has the same name as the
+library is placed in `target/doc/bin` instead. With --open, the index is opened
+when more than one crate was documented.
 
 If the --package argument is given, then SPEC is a package id specification
 which indicates which package should be documented. If it is not given, then the
@@ -64,10 +80,20 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
             no_default_features: options.flag_no_default_features,
             spec: &options.flag_package,
             exec_engine: None,
-            filter: ops::CompileFilter::Everything,
+            filter: ops::CompileFilter::new(false,
+                                            &[],
+                                            options.flag_bins,
+                                            &[],
+                                            options.flag_tests,
+                                            &[],
+                                            options.flag_examples,
+                                            &[],
+                                            options.flag_benches,
+                                            options.flag_all_targets),
             release: options.flag_release,
             mode: ops::CompileMode::Doc {
                 deps: !options.flag_no_deps,
+                private_items: options.flag_document_private_items,
             },
             target_rustc_args: None,
             target_rustdoc_args: None,
@@ -0,0 +1,43 @@
+use cargo::ops;
+use cargo::util::{CliResult, CliError, Config};
+
+#[derive(RustcDecodable)]
+struct Options {
+    flag_max_age: Option<u64>,
+    flag_dry_run: bool,
+    flag_verbose: bool,
+    flag_quiet: bool,
+    flag_color: Option<String>,
+}
+
+pub const USAGE: &'static str = "
+Remove git dependencies which haven't been used in a while
+
+Usage:
+    cargo git-gc [options]
+
+Options:
+    -h, --help               Print this message
+    --max-age DAYS           Remove databases unused for DAYS days (default: 90)
+    -n, --dry-run            Only print what would be removed
+    -v, --verbose            Use verbose output
+    -q, --quiet              No output printed to stdout
+    --color WHEN             Coloring: auto, always, never
+
+Every build which uses a git dependency records when it last did so. This
+command deletes the local clones of git repositories which no build has used
+within the given number of days, along with their checkouts. They are fetched
+again the next time a project depends on them.
+";
+
+pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
+    try!(config.shell().set_verbosity(options.flag_verbose, options.flag_quiet));
+    try!(config.shell().set_color_config(options.flag_color.as_ref().map(|s| &s[..])));
+    let opts = ops::GitGcOptions {
+        config: config,
+        max_age_days: options.flag_max_age.unwrap_or(90),
+        dry_run: options.flag_dry_run,
+    };
+    try!(ops::git_gc(&opts).map_err(|e| CliError::from_boxed(e, 101)));
+    Ok(None)
+}
@@ -0,0 +1,49 @@
+use cargo::ops;
+use cargo::util::{CliResult, CliError, Config};
+
+#[derive(RustcDecodable)]
+struct Options {
+    flag_host: Option<String>,
+    flag_registry: Option<String>,
+    flag_reverse_deps: bool,
+    flag_verbose: bool,
+    flag_quiet: bool,
+    flag_color: Option<String>,
+    arg_crate: String
+}
+
+pub const USAGE: &'static str = "
+Display information about a crate in the registry
+
+Usage:
+    cargo info [options] <crate>
+    cargo info [-h | --help]
+
+Options:
+    -h, --help               Print this message
+    --host HOST              Host of a registry to query
+    --registry REGISTRY      Registry to query
+    --reverse-deps           Also list crates which depend on this one
+    -v, --verbose            Use verbose output
+    -q, --quiet              No output printed to stdout
+    --color WHEN             Coloring: auto, always, never
+
+The crate's description, links, download count and every published version
+are shown. Yanked versions are marked as such.
+";
+
+pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
+    try!(config.shell().set_verbosity(options.flag_verbose, options.flag_quiet));
+    try!(config.shell().set_color_config(options.flag_color.as_ref().map(|s| &s[..])));
+    let Options {
+        flag_host: host,
+        flag_registry: registry,
+        flag_reverse_deps: reverse_deps,
+        arg_crate: krate,
+        ..
+    } = options;
+
+    ops::info(&krate, config, host, registry, reverse_deps)
+        .map(|_| None)
+        .map_err(|err| CliError::from_boxed(err, 101))
+}
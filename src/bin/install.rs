@@ -92,8 +92,12 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
         exec_engine: None,
         mode: ops::CompileMode::Build,
         release: !options.flag_debug,
-        filter: ops::CompileFilter::new(false, &options.flag_bin, &[],
-                                        &options.flag_example, &[]),
+        filter: ops::CompileFilter::new(false,
+                                        &options.flag_bin, false,
+                                        &[], false,
+                                        &options.flag_example, false,
+                                        &[], false,
+                                        false),
         target_rustc_args: None,
         target_rustdoc_args: None,
     };
@@ -9,6 +9,7 @@ use cargo::util::{CliResult, CliError, Config};
 #[derive(RustcDecodable)]
 struct Options {
     flag_host: Option<String>,
+    flag_registry: Option<String>,
     arg_token: Option<String>,
     flag_verbose: bool,
     flag_quiet: bool,
@@ -24,6 +25,7 @@ Usage:
 Options:
     -h, --help               Print this message
     --host HOST              Host to set the token for
+    --registry REGISTRY      Registry to set the token for
     -v, --verbose            Use verbose output
     -q, --quiet              No output printed to stdout
     --color WHEN             Coloring: auto, always, never
@@ -37,7 +39,12 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
         Some(token) => token,
         None => {
             let err = (|| {
-                let src = try!(SourceId::for_central(config));
+                let src = match options.flag_registry {
+                    Some(ref registry) => {
+                        try!(SourceId::alt_registry(config, registry))
+                    }
+                    None => try!(SourceId::for_central(config)),
+                };
                 let mut src = RegistrySource::new(&src, config);
                 try!(src.update());
                 let config = try!(src.config());
@@ -55,7 +62,8 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
     };
 
     let token = token.trim().to_string();
-    try!(ops::registry_login(config, token).map_err(|e| {
+    try!(ops::registry_login(config, token,
+                             options.flag_registry).map_err(|e| {
         CliError::from_boxed(e, 101)
     }));
     Ok(None)
@@ -0,0 +1,34 @@
+use cargo::ops;
+use cargo::util::{CliResult, CliError, Config};
+
+#[derive(RustcDecodable)]
+struct Options {
+    flag_registry: Option<String>,
+    flag_verbose: bool,
+    flag_quiet: bool,
+    flag_color: Option<String>,
+}
+
+pub const USAGE: &'static str = "
+Remove an API token from the registry locally
+
+Usage:
+    cargo logout [options]
+
+Options:
+    -h, --help               Print this message
+    --registry REGISTRY      Registry to remove the token for
+    -v, --verbose            Use verbose output
+    -q, --quiet              No output printed to stdout
+    --color WHEN             Coloring: auto, always, never
+
+";
+
+pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
+    try!(config.shell().set_verbosity(options.flag_verbose, options.flag_quiet));
+    try!(config.shell().set_color_config(options.flag_color.as_ref().map(|s| &s[..])));
+    try!(ops::registry_logout(config, options.flag_registry).map_err(|e| {
+        CliError::from_boxed(e, 101)
+    }));
+    Ok(None)
+}
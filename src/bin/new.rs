@@ -12,6 +12,7 @@ struct Options {
     arg_path: String,
     flag_name: Option<String>,
     flag_vcs: Option<ops::VersionControl>,
+    flag_template: Option<String>,
 }
 
 pub const USAGE: &'static str = "
@@ -28,6 +29,9 @@ Options:
                         control at all (none) overriding a global configuration.
     --bin               Use a binary instead of a library template
     --name NAME         Set the resulting package name
+    --template TEMPLATE Create the package from the skeleton in the directory
+                        or git repository TEMPLATE, as `URL#BRANCH` to use a
+                        branch other than master
     -v, --verbose       Use verbose output
     -q, --quiet         No output printed to stdout
     --color WHEN        Coloring: auto, always, never
@@ -38,13 +42,14 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
     try!(config.shell().set_verbosity(options.flag_verbose, options.flag_quiet));
     try!(config.shell().set_color_config(options.flag_color.as_ref().map(|s| &s[..])));
 
-    let Options { flag_bin, arg_path, flag_name, flag_vcs, .. } = options;
+    let Options { flag_bin, arg_path, flag_name, flag_vcs, flag_template, .. } = options;
 
     let opts = ops::NewOptions {
         version_control: flag_vcs,
         bin: flag_bin,
         path: &arg_path,
         name: flag_name.as_ref().map(|s| s.as_ref()),
+        template: flag_template.as_ref().map(|s| s.as_ref()),
     };
 
     ops::new(opts, config).map(|_| None).map_err(|err| {
@@ -8,6 +8,7 @@ struct Options {
     flag_add: Option<Vec<String>>,
     flag_remove: Option<Vec<String>>,
     flag_index: Option<String>,
+    flag_registry: Option<String>,
     flag_verbose: bool,
     flag_quiet: bool,
     flag_color: Option<String>,
@@ -26,6 +27,7 @@ Options:
     -r, --remove LOGIN       Name of a user or team to remove as an owner
     -l, --list               List owners of a crate
     --index INDEX            Registry index to modify owners for
+    --registry REGISTRY      Registry to modify owners for
     --token TOKEN            API token to use when authenticating
     -v, --verbose            Use verbose output
     -q, --quiet              No output printed to stdout
@@ -47,6 +49,7 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
         krate: options.arg_crate,
         token: options.flag_token,
         index: options.flag_index,
+        registry: options.flag_registry,
         to_add: options.flag_add,
         to_remove: options.flag_remove,
         list: options.flag_list,
@@ -6,11 +6,13 @@ use cargo::util::important_paths::find_root_manifest_for_wd;
 struct Options {
     flag_host: Option<String>,
     flag_token: Option<String>,
+    flag_registry: Option<String>,
     flag_manifest_path: Option<String>,
     flag_verbose: bool,
     flag_quiet: bool,
     flag_color: Option<String>,
     flag_no_verify: bool,
+    flag_dry_run: bool,
 }
 
 pub const USAGE: &'static str = "
@@ -23,7 +25,9 @@ Options:
     -h, --help               Print this message
     --host HOST              Host to upload the package to
     --token TOKEN            Token to use when uploading
+    --registry REGISTRY      Registry to upload the package to
     --no-verify              Don't verify package tarball before publish
+    --dry-run                Perform all checks without uploading
     --manifest-path PATH     Path to the manifest to compile
     -v, --verbose            Use verbose output
     -q, --quiet              No output printed to stdout
@@ -37,13 +41,16 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
     let Options {
         flag_token: token,
         flag_host: host,
+        flag_registry: registry,
         flag_manifest_path,
         flag_no_verify: no_verify,
+        flag_dry_run: dry_run,
         ..
     } = options;
 
     let root = try!(find_root_manifest_for_wd(flag_manifest_path.clone(), config.cwd()));
-    ops::publish(&root, config, token, host, !no_verify).map(|_| None).map_err(|err| {
+    ops::publish(&root, config, token, host, registry,
+                 !no_verify, dry_run).map(|_| None).map_err(|err| {
         CliError::from_boxed(err, 101)
     })
 }
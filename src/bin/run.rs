@@ -76,8 +76,11 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
             ops::CompileFilter::Everything
         } else {
             ops::CompileFilter::Only {
-                lib: false, tests: &[], benches: &[],
-                bins: &bins, examples: &examples,
+                lib: false,
+                tests: ops::FilterRule::Just(&[]),
+                benches: ops::FilterRule::Just(&[]),
+                bins: ops::FilterRule::Just(&bins),
+                examples: ops::FilterRule::Just(&examples),
             }
         },
         target_rustdoc_args: None,
@@ -23,6 +23,11 @@ struct Options {
     flag_example: Vec<String>,
     flag_test: Vec<String>,
     flag_bench: Vec<String>,
+    flag_bins: bool,
+    flag_examples: bool,
+    flag_tests: bool,
+    flag_benches: bool,
+    flag_all_targets: bool,
 }
 
 pub const USAGE: &'static str = "
@@ -40,6 +45,11 @@ Options:
     --example NAME           Build only the specified example
     --test NAME              Build only the specified test target
     --bench NAME             Build only the specified benchmark target
+    --bins                   Build all binaries
+    --examples               Build all examples
+    --tests                  Build all targets that have `test = true` set
+    --benches                Build all targets that have `bench = true` set
+    --all-targets            Build all targets
     --release                Build artifacts in release mode, with optimizations
     --features FEATURES      Features to compile for the package
     --no-default-features    Do not compile default features for the package
@@ -82,9 +92,14 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
         release: options.flag_release,
         filter: ops::CompileFilter::new(options.flag_lib,
                                         &options.flag_bin,
+                                        options.flag_bins,
                                         &options.flag_test,
+                                        options.flag_tests,
                                         &options.flag_example,
-                                        &options.flag_bench),
+                                        options.flag_examples,
+                                        &options.flag_bench,
+                                        options.flag_benches,
+                                        options.flag_all_targets),
         target_rustdoc_args: None,
         target_rustc_args: options.arg_opts.as_ref().map(|a| &a[..]),
     };
@@ -81,10 +81,18 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
             release: options.flag_release,
             filter: ops::CompileFilter::new(options.flag_lib,
                                             &options.flag_bin,
+                                            false,
                                             &options.flag_test,
+                                            false,
                                             &options.flag_example,
-                                            &options.flag_bench),
-            mode: ops::CompileMode::Doc { deps: false },
+                                            false,
+                                            &options.flag_bench,
+                                            false,
+                                            false),
+            mode: ops::CompileMode::Doc {
+                deps: false,
+                private_items: false,
+            },
             target_rustdoc_args: Some(&options.arg_opts),
             target_rustc_args: None,
         },
@@ -4,6 +4,10 @@ use cargo::util::{CliResult, CliError, Config};
 #[derive(RustcDecodable)]
 struct Options {
     flag_host: Option<String>,
+    flag_registry: Option<String>,
+    flag_limit: Option<u32>,
+    flag_sort: Option<String>,
+    flag_json: bool,
     flag_verbose: bool,
     flag_quiet: bool,
     flag_color: Option<String>,
@@ -20,6 +24,10 @@ Usage:
 Options:
     -h, --help               Print this message
     --host HOST              Host of a registry to search in
+    --registry REGISTRY      Registry to search in
+    --limit LIMIT            Limit the number of results (default: 10)
+    --sort ORDER             Sort by relevance, downloads or recent
+    --json                   Print the results as JSON
     -v, --verbose            Use verbose output
     -q, --quiet              No output printed to stdout
     --color WHEN             Coloring: auto, always, never
@@ -30,11 +38,22 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
     try!(config.shell().set_color_config(options.flag_color.as_ref().map(|s| &s[..])));
     let Options {
         flag_host: host,
+        flag_registry: registry,
+        flag_limit: limit,
+        flag_sort: sort,
+        flag_json: json,
         arg_query: query,
         ..
     } = options;
 
-    ops::search(&query, config, host)
+    let opts = ops::SearchOptions {
+        index: host,
+        registry: registry,
+        limit: limit.unwrap_or(10),
+        sort: sort,
+        json: json,
+    };
+    ops::search(&query, config, opts)
         .map(|_| None)
         .map_err(|err| CliError::from_boxed(err, 101))
 }
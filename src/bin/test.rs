@@ -17,11 +17,20 @@ struct Options {
     flag_example: Vec<String>,
     flag_test: Vec<String>,
     flag_bench: Vec<String>,
+    flag_bins: bool,
+    flag_examples: bool,
+    flag_tests: bool,
+    flag_benches: bool,
+    flag_all_targets: bool,
     flag_verbose: bool,
     flag_quiet: bool,
     flag_color: Option<String>,
     flag_release: bool,
     flag_no_fail_fast: bool,
+    flag_list: bool,
+    flag_exact: bool,
+    flag_test_jobs: Option<u32>,
+    flag_report: Option<ops::TestReport>,
 }
 
 pub const USAGE: &'static str = "
@@ -37,9 +46,15 @@ Options:
     --example NAME               Test only the specified example
     --test NAME                  Test only the specified integration test target
     --bench NAME                 Test only the specified benchmark target
+    --bins                       Test all binaries
+    --examples                   Test all examples
+    --tests                      Test all targets that have `test = true` set
+    --benches                    Test all targets that have `bench = true` set
+    --all-targets                Test all targets
     --no-run                     Compile, but don't run tests
     -p SPEC, --package SPEC ...  Package to run tests for
     -j N, --jobs N               The number of jobs to run in parallel
+    --test-jobs N                The number of test binaries to run in parallel
     --release                    Build artifacts in release mode, with optimizations
     --features FEATURES          Space-separated list of features to also build
     --no-default-features        Do not build the `default` feature
@@ -49,6 +64,11 @@ Options:
     -q, --quiet                  No output printed to stdout
     --color WHEN                 Coloring: auto, always, never
     --no-fail-fast               Run all tests regardless of failure
+    --list                       List the tests in every test target, including
+                                 doc tests, without running them
+    --exact                      Only run tests whose name is exactly <args>
+    --report FORMAT=PATH         Write the result of every test to PATH, where
+                                 FORMAT is `junit` or `json`
 
 All of the trailing arguments are passed to the test binaries generated for
 filtering tests and generally providing options configuring how they run. For
@@ -56,13 +76,30 @@ example, this will run all tests with the name `foo` in their name:
 
     cargo test foo
 
+With --exact, only the test named `foo` would run. When tests fail, Cargo
+prints the command which runs just the failed test binary and test again.
+Doc tests can't be selected on their own, so the command for a failed doc test
+starts every test binary, though only tests with that exact name run.
+
 If the --package argument is given, then SPEC is a package id specification
 which indicates which package should be tested. If it is not given, then the
 current package is tested. For more information on SPEC and its format, see the
 `cargo help pkgid` command.
 
 The --jobs argument affects the building of the test executable but does
-not affect how many jobs are used when running the tests.
+not affect how many jobs are used when running the tests. Test binaries, doc
+tests included, are run one at a time unless --test-jobs or the `test.jobs`
+configuration key asks for more. The output of each binary is then printed
+all at once when it finishes, so it isn't mixed up with the others.
+
+The --report argument writes one file covering every test binary that ran,
+doc tests included, such as `--report junit=target/report.xml` for CI systems
+which read JUnit XML. Each test is recorded as passed, failed or ignored, along
+with the output of failed tests and how long each test and binary took. A test
+is timed from when the result of the one before it was printed, so test times
+are only exact when the harness runs one test at a time, as with
+`RUST_TEST_THREADS=1`. Binaries run with --test-jobs above 1 only have their
+overall time recorded.
 
 Compilation can be configured via the `test` profile in the manifest.
 ";
@@ -75,6 +112,10 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
     let ops = ops::TestOptions {
         no_run: options.flag_no_run,
         no_fail_fast: options.flag_no_fail_fast,
+        list: options.flag_list,
+        exact: options.flag_exact,
+        test_jobs: options.flag_test_jobs,
+        report: options.flag_report,
         compile_opts: ops::CompileOptions {
             config: config,
             jobs: options.flag_jobs,
@@ -87,16 +128,20 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
             mode: ops::CompileMode::Test,
             filter: ops::CompileFilter::new(options.flag_lib,
                                             &options.flag_bin,
+                                            options.flag_bins,
                                             &options.flag_test,
+                                            options.flag_tests,
                                             &options.flag_example,
-                                            &options.flag_bench),
+                                            options.flag_examples,
+                                            &options.flag_bench,
+                                            options.flag_benches,
+                                            options.flag_all_targets),
             target_rustdoc_args: None,
             target_rustc_args: None,
         },
     };
 
-    let err = try!(ops::run_tests(&root, &ops,
-                                  &options.arg_args).map_err(|err| {
+    let err = try!(ops::run_tests(&root, &ops, &options.arg_args).map_err(|err| {
         CliError::from_boxed(err, 101)
     }));
     match err {
@@ -7,6 +7,7 @@ struct Options {
     flag_token: Option<String>,
     flag_vers: Option<String>,
     flag_index: Option<String>,
+    flag_registry: Option<String>,
     flag_verbose: bool,
     flag_quiet: bool,
     flag_color: Option<String>,
@@ -20,14 +21,15 @@ Usage:
     cargo yank [options] [<crate>]
 
 Options:
-    -h, --help          Print this message
-    --vers VERSION      The version to yank or un-yank
-    --undo              Undo a yank, putting a version back into the index
-    --index INDEX       Registry index to yank from
-    --token TOKEN       API token to use when authenticating
-    -v, --verbose       Use verbose output
-    -q, --quiet         No output printed to stdout
-    --color WHEN        Coloring: auto, always, never
+    -h, --help              Print this message
+    --vers VERSION          The version to yank or un-yank
+    --undo                  Undo a yank, putting a version back into the index
+    --index INDEX           Registry index to yank from
+    --registry REGISTRY     Registry to yank from
+    --token TOKEN           API token to use when authenticating
+    -v, --verbose           Use verbose output
+    -q, --quiet             No output printed to stdout
+    --color WHEN            Coloring: auto, always, never
 
 The yank command removes a previously pushed crate's version from the server's
 index. This command does not delete any data, and the crate will still be
@@ -46,6 +48,7 @@ pub fn execute(options: Options, config: &Config) -> CliResult<Option<()>> {
                    options.flag_vers,
                    options.flag_token,
                    options.flag_index,
+                   options.flag_registry,
                    options.flag_undo).map_err(|e| {
         CliError::from_boxed(e, 101)
     }));
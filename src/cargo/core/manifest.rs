@@ -21,6 +21,7 @@ pub struct Manifest {
     include: Vec<String>,
     metadata: ManifestMetadata,
     profiles: Profiles,
+    publish: Option<Vec<String>>,
 }
 
 /// General metadata about a package which is just blindly uploaded to the
@@ -119,6 +120,7 @@ pub struct Profile {
     pub test: bool,
     pub doc: bool,
     pub run_custom_build: bool,
+    pub check: bool,
 }
 
 #[derive(Default, Clone, Debug)]
@@ -129,6 +131,10 @@ pub struct Profiles {
     pub bench: Profile,
     pub doc: Profile,
     pub custom_build: Profile,
+    pub check: Profile,
+    pub check_release: Profile,
+    pub check_test: Profile,
+    pub check_bench: Profile,
 }
 
 /// Information about a binary, a library, an example, etc. that is part of the
@@ -145,6 +151,7 @@ pub struct Target {
     doctest: bool,
     harness: bool, // whether to use the test harness (--test)
     for_host: bool,
+    required_features: Option<Vec<String>>,
 }
 
 #[derive(RustcEncodable)]
@@ -183,7 +190,8 @@ impl Manifest {
                include: Vec<String>,
                links: Option<String>,
                metadata: ManifestMetadata,
-               profiles: Profiles) -> Manifest {
+               profiles: Profiles,
+               publish: Option<Vec<String>>) -> Manifest {
         Manifest {
             summary: summary,
             targets: targets,
@@ -193,6 +201,7 @@ impl Manifest {
             links: links,
             metadata: metadata,
             profiles: profiles,
+            publish: publish,
         }
     }
 
@@ -207,6 +216,10 @@ impl Manifest {
     pub fn version(&self) -> &Version { self.package_id().version() }
     pub fn warnings(&self) -> &[String] { &self.warnings }
     pub fn profiles(&self) -> &Profiles { &self.profiles }
+
+    /// The registries this package may be published to, or `None` if it may
+    /// be published anywhere. An empty list means it may not be published.
+    pub fn publish(&self) -> Option<&Vec<String>> { self.publish.as_ref() }
     pub fn links(&self) -> Option<&str> {
         self.links.as_ref().map(|s| &s[..])
     }
@@ -233,6 +246,7 @@ impl Target {
             for_host: false,
             tested: true,
             benched: true,
+            required_features: None,
         }
     }
 
@@ -322,6 +336,11 @@ impl Target {
     pub fn for_host(&self) -> bool { self.for_host }
     pub fn benched(&self) -> bool { self.benched }
 
+    /// Features which must be enabled for this target to be built, if any.
+    pub fn required_features(&self) -> Option<&Vec<String>> {
+        self.required_features.as_ref()
+    }
+
     pub fn doctested(&self) -> bool {
         self.doctest && match self.kind {
             TargetKind::Lib(ref kinds) => {
@@ -407,6 +426,11 @@ impl Target {
         self.doc = doc;
         self
     }
+    pub fn set_required_features(&mut self, features: Option<Vec<String>>)
+                                 -> &mut Target {
+        self.required_features = features;
+        self
+    }
 }
 
 impl fmt::Display for Target {
@@ -466,6 +490,34 @@ impl Profile {
             ..Profile::default_dev()
         }
     }
+
+    pub fn default_check() -> Profile {
+        Profile {
+            check: true,
+            ..Profile::default_dev()
+        }
+    }
+
+    pub fn default_check_release() -> Profile {
+        Profile {
+            check: true,
+            ..Profile::default_release()
+        }
+    }
+
+    pub fn default_check_test() -> Profile {
+        Profile {
+            check: true,
+            ..Profile::default_test()
+        }
+    }
+
+    pub fn default_check_bench() -> Profile {
+        Profile {
+            check: true,
+            ..Profile::default_bench()
+        }
+    }
 }
 
 impl Default for Profile {
@@ -482,6 +534,7 @@ impl Default for Profile {
             test: false,
             doc: false,
             run_custom_build: false,
+            check: false,
         }
     }
 }
@@ -494,6 +547,8 @@ impl fmt::Display for Profile {
             write!(f, "Profile(doc)")
         } else if self.run_custom_build {
             write!(f, "Profile(run)")
+        } else if self.check {
+            write!(f, "Profile(check)")
         } else {
             write!(f, "Profile(build)")
         }
@@ -8,6 +8,7 @@ use core::{Dependency, Manifest, PackageId, SourceId, Registry, Target, Summary,
 use ops;
 use core::dependency::SerializedDependency;
 use util::{CargoResult, graph, Config};
+use util::important_paths::find_root_manifest_for_wd;
 use rustc_serialize::{Encoder,Encodable};
 use core::source::Source;
 
@@ -67,6 +68,15 @@ impl Package {
         Ok(pkg)
     }
 
+    /// Loads the package at `manifest_path`, or the one containing the
+    /// current directory, the same way a built-in command given
+    /// `--manifest-path` would find it.
+    pub fn for_cwd(manifest_path: Option<String>, config: &Config)
+                   -> CargoResult<Package> {
+        let root = try!(find_root_manifest_for_wd(manifest_path, config.cwd()));
+        Package::for_path(&root, config)
+    }
+
     pub fn dependencies(&self) -> &[Dependency] { self.manifest.dependencies() }
     pub fn manifest(&self) -> &Manifest { &self.manifest }
     pub fn manifest_path(&self) -> &Path { &self.manifest_path }
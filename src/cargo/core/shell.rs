@@ -26,6 +26,16 @@ pub enum ColorConfig {
     Never
 }
 
+impl fmt::Display for ColorConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Auto => "auto",
+            Always => "always",
+            Never => "never",
+        }.fmt(f)
+    }
+}
+
 #[derive(Clone, Copy)]
 pub struct ShellConfig {
     pub color_config: ColorConfig,
@@ -40,6 +50,8 @@ enum AdequateTerminal {
 pub struct Shell {
     terminal: AdequateTerminal,
     config: ShellConfig,
+    /// Width of the progress line currently on screen, if any
+    progress_width: usize,
 }
 
 pub struct MultiShell {
@@ -77,6 +89,24 @@ impl MultiShell {
         }
     }
 
+    /// Display a status line which is overwritten by the next call to
+    /// `progress` and erased by `clear_progress`.
+    ///
+    /// Progress is only shown when stdout is a terminal.
+    pub fn progress<T, U>(&mut self, status: T, message: U) -> io::Result<()>
+        where T: fmt::Display, U: fmt::Display
+    {
+        match self.verbosity {
+            Quiet => Ok(()),
+            _ if !self.out.config.tty => Ok(()),
+            _ => self.out().say_progress(status, message, GREEN)
+        }
+    }
+
+    pub fn clear_progress(&mut self) -> io::Result<()> {
+        self.out().clear_progress()
+    }
+
     pub fn verbose<F>(&mut self, mut callback: F) -> io::Result<()>
         where F: FnMut(&mut MultiShell) -> io::Result<()>
     {
@@ -139,6 +169,10 @@ impl MultiShell {
     pub fn get_verbose(&self) -> Verbosity {
         self.verbosity
     }
+
+    pub fn color_config(&self) -> ColorConfig {
+        self.out.config.color_config
+    }
 }
 
 impl Shell {
@@ -152,16 +186,21 @@ impl Shell {
                 // Color output is possible.
                 Shell {
                     terminal: Colored(Box::new(TerminfoTerminal::new_with_terminfo(out, ti))),
-                    config: config
+                    config: config,
+                    progress_width: 0,
                 }
             }
             _ if config.tty => {
                 // Color output is expected but not available, fall back to stderr.
-                Shell { terminal: NoColor(Box::new(io::stderr())), config: config }
+                Shell {
+                    terminal: NoColor(Box::new(io::stderr())),
+                    config: config,
+                    progress_width: 0,
+                }
             }
             _ => {
                 // No color output.
-                Shell { terminal: NoColor(out), config: config }
+                Shell { terminal: NoColor(out), config: config, progress_width: 0 }
             }
         }
     }
@@ -193,6 +232,38 @@ impl Shell {
         Ok(())
     }
 
+    pub fn say_progress<T, U>(&mut self, status: T, message: U, color: Color)
+                              -> io::Result<()>
+        where T: fmt::Display, U: fmt::Display
+    {
+        let status = format!("{:>12}", status);
+        let message = message.to_string();
+        let width = status.len() + 1 + message.len();
+        try!(self.reset());
+        try!(write!(self, "\r"));
+        if color != BLACK { try!(self.fg(color)); }
+        if self.supports_attr(Attr::Bold) { try!(self.attr(Attr::Bold)); }
+        try!(write!(self, "{}", status));
+        try!(self.reset());
+        try!(write!(self, " {}", message));
+        // Blank out whatever is left of a longer previous line
+        for _ in width..self.progress_width {
+            try!(write!(self, " "));
+        }
+        try!(self.flush());
+        self.progress_width = width;
+        Ok(())
+    }
+
+    pub fn clear_progress(&mut self) -> io::Result<()> {
+        if self.progress_width == 0 { return Ok(()) }
+        let blank = (0..self.progress_width).map(|_| ' ').collect::<String>();
+        try!(write!(self, "\r{}\r", blank));
+        try!(self.flush());
+        self.progress_width = 0;
+        Ok(())
+    }
+
     fn fg(&mut self, color: color::Color) -> io::Result<bool> {
         let colored = self.colored();
 
@@ -229,7 +300,8 @@ impl Shell {
         }
     }
 
-    fn colored(&self) -> bool {
+    /// Whether what's written to this shell is colored.
+    pub fn colored(&self) -> bool {
         self.config.tty && Auto == self.config.color_config
             || Always == self.config.color_config
     }
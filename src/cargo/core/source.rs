@@ -183,6 +183,12 @@ impl SourceId {
         Ok(SourceId::for_registry(&try!(RegistrySource::url(config))))
     }
 
+    /// Returns the `SourceId` of the registry named `name` in the
+    /// `[registries]` table of a `.cargo/config`.
+    pub fn alt_registry(config: &Config, name: &str) -> CargoResult<SourceId> {
+        Ok(SourceId::for_registry(&try!(RegistrySource::alt_url(config, name))))
+    }
+
     pub fn url(&self) -> &Url { &self.inner.url }
     pub fn is_path(&self) -> bool { self.inner.kind == Kind::Path }
     pub fn is_registry(&self) -> bool { self.inner.kind == Kind::Registry }
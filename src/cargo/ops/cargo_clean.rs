@@ -71,9 +71,12 @@ pub fn clean(manifest_path: &Path, opts: &CleanOptions) -> CargoResult<()> {
                 try!(rm_rf(&layout.build(&pkg)));
                 let Profiles {
                     ref release, ref dev, ref test, ref bench, ref doc,
-                    ref custom_build,
+                    ref custom_build, ref check, ref check_release,
+                    ref check_test, ref check_bench,
                 } = *root.manifest().profiles();
-                for profile in [release, dev, test, bench, doc, custom_build].iter() {
+                for profile in [release, dev, test, bench, doc, custom_build,
+                                check, check_release, check_test,
+                                check_bench].iter() {
                     let unit = Unit {
                         pkg: &pkg,
                         target: target,
@@ -22,7 +22,7 @@
 //!       previously compiled dependency
 //!
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::default::Default;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -33,7 +33,7 @@ use core::{Profile, TargetKind, Profiles};
 use core::resolver::{Method, Resolve};
 use ops::{self, BuildOutput, ExecEngine};
 use util::config::{ConfigValue, Config};
-use util::{CargoResult, internal, ChainError, profile};
+use util::{CargoResult, profile};
 
 /// Contains information about how a package should be compiled.
 pub struct CompileOptions<'a> {
@@ -68,21 +68,32 @@ pub struct CompileOptions<'a> {
 pub enum CompileMode {
     Test,
     Build,
+    Check,
     Bench,
-    Doc { deps: bool },
+    /// Document the selected packages, along with their dependencies if
+    /// `deps` is set. `private_items` also documents the private items of the
+    /// selected packages, though never those of dependencies.
+    Doc { deps: bool, private_items: bool },
 }
 
 pub enum CompileFilter<'a> {
     Everything,
     Only {
         lib: bool,
-        bins: &'a [String],
-        examples: &'a [String],
-        tests: &'a [String],
-        benches: &'a [String],
+        bins: FilterRule<'a>,
+        examples: FilterRule<'a>,
+        tests: FilterRule<'a>,
+        benches: FilterRule<'a>,
     }
 }
 
+/// Selects the targets of one kind, either all of them (`--bins`) or only
+/// those named explicitly (`--bin NAME`).
+pub enum FilterRule<'a> {
+    All,
+    Just(&'a [String]),
+}
+
 pub fn compile<'a>(manifest_path: &Path,
                    options: &CompileOptions<'a>)
                    -> CargoResult<ops::Compilation<'a>> {
@@ -185,6 +196,7 @@ pub fn compile_pkg<'a>(root_package: &Package,
                             .collect::<Vec<_>>();
 
     let mut general_targets = Vec::new();
+    let mut private_doc_targets = Vec::new();
     let mut package_targets = Vec::new();
 
     let profiles = root_package.manifest().profiles();
@@ -194,8 +206,11 @@ pub fn compile_pkg<'a>(root_package: &Package,
             panic!("`rustc` and `rustdoc` should not accept multiple `-p` flags")
         }
         (Some(args), _) => {
+            let features = package_features(&resolve_with_overrides,
+                                            to_builds[0]);
             let targets = try!(generate_targets(to_builds[0], profiles,
-                                                mode, filter, release));
+                                                mode, filter, &features,
+                                                release));
             if targets.len() == 1 {
                 let (target, profile) = targets[0];
                 let mut profile = profile.clone();
@@ -208,8 +223,11 @@ pub fn compile_pkg<'a>(root_package: &Package,
             }
         }
         (None, Some(args)) => {
+            let features = package_features(&resolve_with_overrides,
+                                            to_builds[0]);
             let targets = try!(generate_targets(to_builds[0], profiles,
-                                                mode, filter, release));
+                                                mode, filter, &features,
+                                                release));
             if targets.len() == 1 {
                 let (target, profile) = targets[0];
                 let mut profile = profile.clone();
@@ -223,13 +241,30 @@ pub fn compile_pkg<'a>(root_package: &Package,
         }
         (None, None) => {
             for &to_build in to_builds.iter() {
+                let features = package_features(&resolve_with_overrides,
+                                                to_build);
                 let targets = try!(generate_targets(to_build, profiles, mode,
-                                                    filter, release));
-                package_targets.push((to_build, targets));
+                                                    filter, &features,
+                                                    release));
+                if let CompileMode::Doc { private_items: true, .. } = mode {
+                    for (target, profile) in targets {
+                        let mut profile = profile.clone();
+                        profile.rustdoc_args = Some(vec![
+                            "--document-private-items".to_string(),
+                        ]);
+                        private_doc_targets.push((to_build, target, profile));
+                    }
+                } else {
+                    package_targets.push((to_build, targets));
+                }
             }
         }
     };
 
+    for &(to_build, target, ref profile) in &private_doc_targets {
+        package_targets.push((to_build, vec![(target, profile)]));
+    }
+
     for &(target, ref profile) in &general_targets {
         for &to_build in to_builds.iter() {
             package_targets.push((to_build, vec![(target, profile)]));
@@ -241,7 +276,7 @@ pub fn compile_pkg<'a>(root_package: &Package,
         let mut build_config = try!(scrape_build_config(config, jobs, target));
         build_config.exec_engine = exec_engine.clone();
         build_config.release = release;
-        if let CompileMode::Doc { deps } = mode {
+        if let CompileMode::Doc { deps, .. } = mode {
             build_config.doc_all = deps;
         }
 
@@ -262,12 +297,23 @@ pub fn compile_pkg<'a>(root_package: &Package,
 
 impl<'a> CompileFilter<'a> {
     pub fn new(lib_only: bool,
-               bins: &'a [String],
-               tests: &'a [String],
-               examples: &'a [String],
-               benches: &'a [String]) -> CompileFilter<'a> {
-        if lib_only || !bins.is_empty() || !tests.is_empty() ||
-           !examples.is_empty() || !benches.is_empty() {
+               bins: &'a [String], all_bins: bool,
+               tests: &'a [String], all_tests: bool,
+               examples: &'a [String], all_examples: bool,
+               benches: &'a [String], all_benches: bool,
+               all_targets: bool) -> CompileFilter<'a> {
+        if all_targets {
+            return CompileFilter::Only {
+                lib: true, bins: FilterRule::All, examples: FilterRule::All,
+                tests: FilterRule::All, benches: FilterRule::All,
+            }
+        }
+        let bins = FilterRule::new(bins, all_bins);
+        let tests = FilterRule::new(tests, all_tests);
+        let examples = FilterRule::new(examples, all_examples);
+        let benches = FilterRule::new(benches, all_benches);
+        if lib_only || bins.is_specific() || tests.is_specific() ||
+           examples.is_specific() || benches.is_specific() {
             CompileFilter::Only {
                 lib: lib_only, bins: bins, examples: examples, benches: benches,
                 tests: tests,
@@ -280,8 +326,24 @@ impl<'a> CompileFilter<'a> {
     pub fn matches(&self, target: &Target) -> bool {
         match *self {
             CompileFilter::Everything => true,
-            CompileFilter::Only { lib, bins, examples, tests, benches } => {
-                let list = match *target.kind() {
+            CompileFilter::Only { lib, ref bins, ref examples, ref tests,
+                                  ref benches } => {
+                // `--tests` and `--benches` select every target with
+                // `test = true` or `bench = true` set, just as
+                // `generate_targets` expands them.
+                if let FilterRule::All = *tests {
+                    if target.tested() && !target.is_example() &&
+                       !target.is_custom_build() {
+                        return true
+                    }
+                }
+                if let FilterRule::All = *benches {
+                    if target.benched() && !target.is_example() &&
+                       !target.is_custom_build() {
+                        return true
+                    }
+                }
+                let rule = match *target.kind() {
                     TargetKind::Bin => bins,
                     TargetKind::Test => tests,
                     TargetKind::Bench => benches,
@@ -289,40 +351,109 @@ impl<'a> CompileFilter<'a> {
                     TargetKind::Lib(..) => return lib,
                     TargetKind::CustomBuild => return false,
                 };
-                list.iter().any(|x| *x == target.name())
+                rule.matches(target)
+            }
+        }
+    }
+
+    /// Whether this filter selects every target, as with `--all-targets`.
+    fn is_all_targets(&self) -> bool {
+        match *self {
+            CompileFilter::Only { lib: true, bins: FilterRule::All,
+                                  examples: FilterRule::All,
+                                  tests: FilterRule::All,
+                                  benches: FilterRule::All } => true,
+            _ => false,
+        }
+    }
+}
+
+impl<'a> FilterRule<'a> {
+    pub fn new(targets: &'a [String], all: bool) -> FilterRule<'a> {
+        if all {
+            FilterRule::All
+        } else {
+            FilterRule::Just(targets)
+        }
+    }
+
+    fn is_specific(&self) -> bool {
+        match *self {
+            FilterRule::All => true,
+            FilterRule::Just(targets) => !targets.is_empty(),
+        }
+    }
+
+    pub fn matches(&self, target: &Target) -> bool {
+        match *self {
+            FilterRule::All => true,
+            FilterRule::Just(targets) => {
+                targets.iter().any(|x| *x == target.name())
             }
         }
     }
 }
 
+/// Returns the set of features activated for `pkg` in `resolve`.
+fn package_features(resolve: &Resolve, pkg: &Package) -> HashSet<String> {
+    resolve.features(pkg.package_id()).cloned().unwrap_or(HashSet::new())
+}
+
+/// Returns the `required-features` of `target` which are not enabled.
+fn missing_features<'a>(target: &'a Target,
+                        features: &HashSet<String>) -> Vec<&'a str> {
+    match target.required_features() {
+        Some(required) => {
+            required.iter().filter(|f| !features.contains(*f))
+                    .map(|f| &f[..]).collect()
+        }
+        None => Vec::new(),
+    }
+}
+
 /// Given the configuration for a build, this function will generate all
 /// target/profile combinations needed to be built.
+///
+/// Targets whose `required-features` are not all enabled are skipped, unless
+/// they were asked for by name in which case an error is returned.
 fn generate_targets<'a>(pkg: &'a Package,
                         profiles: &'a Profiles,
                         mode: CompileMode,
                         filter: &CompileFilter,
+                        features: &HashSet<String>,
                         release: bool)
                         -> CargoResult<Vec<(&'a Target, &'a Profile)>> {
     let build = if release {&profiles.release} else {&profiles.dev};
     let test = if release {&profiles.bench} else {&profiles.test};
+    let check = if release {&profiles.check_release} else {&profiles.check};
+    let check_test = if release {
+        &profiles.check_bench
+    } else {
+        &profiles.check_test
+    };
     let profile = match mode {
         CompileMode::Test => test,
         CompileMode::Bench => &profiles.bench,
         CompileMode::Build => build,
+        CompileMode::Check => check,
         CompileMode::Doc { .. } => &profiles.doc,
     };
     return match *filter {
         CompileFilter::Everything => {
+            let available = |t: &&Target| {
+                missing_features(t, features).is_empty()
+            };
             match mode {
                 CompileMode::Bench => {
-                    Ok(pkg.targets().iter().filter(|t| t.benched()).map(|t| {
+                    Ok(pkg.targets().iter().filter(|t| t.benched())
+                          .filter(&available).map(|t| {
                         (t, profile)
                     }).collect::<Vec<_>>())
                 }
                 CompileMode::Test => {
                     let mut base = pkg.targets().iter().filter(|t| {
                         t.tested()
-                    }).map(|t| {
+                    }).filter(&available).map(|t| {
                         (t, if t.is_example() {build} else {profile})
                     }).collect::<Vec<_>>();
 
@@ -335,30 +466,60 @@ fn generate_targets<'a>(pkg: &'a Package,
                     }
                     Ok(base)
                 }
-                CompileMode::Build => {
+                CompileMode::Build | CompileMode::Check => {
                     Ok(pkg.targets().iter().filter(|t| {
                         t.is_bin() || t.is_lib()
-                    }).map(|t| (t, profile)).collect())
+                    }).filter(&available).map(|t| (t, profile)).collect())
                 }
                 CompileMode::Doc { .. } => {
                     Ok(pkg.targets().iter().filter(|t| t.documented())
+                          .filter(&available)
                           .map(|t| (t, profile)).collect())
                 }
             }
         }
-        CompileFilter::Only { lib, bins, examples, tests, benches } => {
+        CompileFilter::Only { lib, ref bins, ref examples, ref tests,
+                              ref benches } => {
             let mut targets = Vec::new();
 
             if lib {
                 if let Some(t) = pkg.targets().iter().find(|t| t.is_lib()) {
                     targets.push((t, profile));
-                } else {
+                } else if !filter.is_all_targets() {
                     bail!("no library targets found")
                 }
             }
 
             {
-                let mut find = |names: &[String], desc, kind, profile| {
+                let mut find = |rule: &FilterRule, desc, kind: TargetKind,
+                                profile| {
+                    let names = match *rule {
+                        FilterRule::Just(names) => names,
+                        FilterRule::All => {
+                            // Expand against every target of this kind, using
+                            // the `test`/`bench` flags for the plural test and
+                            // bench selectors so `test = false` is honored.
+                            for t in pkg.targets().iter().filter(|t| {
+                                match kind {
+                                    TargetKind::Test => {
+                                        t.tested() && !t.is_example() &&
+                                            !t.is_custom_build()
+                                    }
+                                    TargetKind::Bench => {
+                                        t.benched() && !t.is_example() &&
+                                            !t.is_custom_build()
+                                    }
+                                    _ => *t.kind() == kind,
+                                }
+                            }) {
+                                if missing_features(t, features).is_empty() &&
+                                   !targets.contains(&(t, profile)) {
+                                    targets.push((t, profile));
+                                }
+                            }
+                            return Ok(())
+                        }
+                    };
                     for name in names {
                         let target = pkg.targets().iter().find(|t| {
                             t.name() == *name && *t.kind() == kind
@@ -367,15 +528,28 @@ fn generate_targets<'a>(pkg: &'a Package,
                             Some(t) => t,
                             None => bail!("no {} target named `{}`", desc, name),
                         };
+                        let missing = missing_features(t, features);
+                        if !missing.is_empty() {
+                            bail!("target `{}` requires the features: `{}`\n\
+                                   Consider enabling them by passing e.g. \
+                                   `--features=\"{}\"`", name,
+                                  missing.join("`, `"), missing.join(" "))
+                        }
                         debug!("found {} `{}`", desc, name);
                         targets.push((t, profile));
                     }
                     Ok(())
                 };
+                let (example, test, bench) = match mode {
+                    CompileMode::Doc { .. } => (profile, profile, profile),
+                    CompileMode::Check => (profile, check_test,
+                                           &profiles.check_bench),
+                    _ => (build, test, &profiles.bench),
+                };
                 try!(find(bins, "bin", TargetKind::Bin, profile));
-                try!(find(examples, "example", TargetKind::Example, build));
+                try!(find(examples, "example", TargetKind::Example, example));
                 try!(find(tests, "test", TargetKind::Test, test));
-                try!(find(benches, "bench", TargetKind::Bench, &profiles.bench));
+                try!(find(benches, "bench", TargetKind::Bench, bench));
             }
             Ok(targets)
         }
@@ -387,21 +561,15 @@ fn generate_targets<'a>(pkg: &'a Package,
 fn source_ids_from_config(config: &Config, cur_path: &Path)
                           -> CargoResult<Vec<SourceId>> {
 
-    let configs = try!(config.values());
-    debug!("loaded config; configs={:?}", configs);
-    let config_paths = match configs.get("paths") {
-        Some(cfg) => cfg,
+    let paths = match try!(config.get_list("paths")) {
+        Some((paths, _)) => paths,
         None => return Ok(Vec::new())
     };
-    let paths = try!(config_paths.list().chain_error(|| {
-        internal("invalid configuration for the key `paths`")
-    }));
 
     paths.iter().map(|&(ref s, ref p)| {
-        // The path listed next to the string is the config file in which the
-        // key was located, so we want to pop off the `.cargo/config` component
-        // to get the directory containing the `.cargo` folder.
-        p.parent().unwrap().parent().unwrap().join(s)
+        // The path listed next to the string is where it was defined, either
+        // a config file or an environment variable.
+        config.definition_root(p).join(s)
     }).filter(|p| {
         // Make sure we don't override the local package, even if it's in the
         // list of override paths.
@@ -415,6 +583,7 @@ fn source_ids_from_config(config: &Config, cur_path: &Path)
 /// * build.jobs
 /// * target.$target.ar
 /// * target.$target.linker
+/// * target.$target.runner
 /// * target.$target.libfoo.metadata
 fn scrape_build_config(config: &Config,
                        jobs: Option<u32>,
@@ -453,6 +622,7 @@ fn scrape_target_config(config: &Config, triple: &str)
     let mut ret = ops::TargetConfig {
         ar: try!(config.get_path(&format!("{}.ar", key))),
         linker: try!(config.get_path(&format!("{}.linker", key))),
+        runner: try!(config.get_path_and_args(&format!("{}.runner", key))),
         overrides: HashMap::new(),
     };
     let table = match try!(config.get_table(&key)) {
@@ -460,13 +630,20 @@ fn scrape_target_config(config: &Config, triple: &str)
         None => return Ok(ret),
     };
     for (lib_name, _) in table.into_iter() {
-        if lib_name == "ar" || lib_name == "linker" { continue }
+        if lib_name == "ar" || lib_name == "linker" || lib_name == "runner" {
+            continue
+        }
 
         let mut output = BuildOutput {
             library_paths: Vec::new(),
             library_links: Vec::new(),
             cfgs: Vec::new(),
             metadata: Vec::new(),
+            env: Vec::new(),
+            link_args: Vec::new(),
+            warnings: Vec::new(),
+            rerun_if_changed: Vec::new(),
+            rerun_if_env_changed: Vec::new(),
         };
         let key = format!("{}.{}", key, lib_name);
         let table = try!(config.get_table(&key)).unwrap().0;
@@ -495,6 +672,8 @@ fn scrape_target_config(config: &Config, triple: &str)
                         }));
                     } else if k == "rustc-cfg" {
                         output.cfgs.extend(a.into_iter().map(|v| v.0));
+                    } else if k == "rustc-link-arg" {
+                        output.link_args.extend(a.into_iter().map(|v| v.0));
                     } else {
                         try!(config.expected("string", &k,
                                              ConfigValue::List(a, p)));
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use util::{CargoResult, Config};
+use util::config::{self, ConfigValue, Location};
+use util::toml as cargo_toml;
+
+/// Print the effective value of `key` and where it was defined.
+pub fn config_get(config: &Config, key: &str) -> CargoResult<()> {
+    if let Some(value) = try!(config.get(key)) {
+        print_value(key, &value);
+        return Ok(())
+    }
+    match try!(config.get_table(key)) {
+        Some((table, path)) => {
+            print_value(key, &ConfigValue::Table(table, path));
+            Ok(())
+        }
+        None => bail!("configuration key `{}` is not set", key),
+    }
+}
+
+/// Print every value from all configuration files, merged, along with the
+/// file each one came from.
+pub fn config_list(config: &Config) -> CargoResult<()> {
+    let values = try!(config.values());
+    let values = values.iter().collect::<BTreeMap<_, _>>();
+    for (key, value) in values.into_iter() {
+        print_value(key, value);
+    }
+    Ok(())
+}
+
+/// Set `key` in the configuration file for `loc`.
+///
+/// `value` is read as a TOML value if it is one, such as `4`, `true` or
+/// `["a", "b"]`, and is taken as a plain string otherwise, so neither
+/// `1.0` nor `http://example.com` need to be quoted.
+pub fn config_set(config: &Config, loc: Location, key: &str, value: &str)
+                  -> CargoResult<()> {
+    let source = Path::new("the command line");
+    let parsed = cargo_toml::parse(&format!("value = {}", value), source).ok()
+                            .and_then(|mut table| table.remove("value"))
+                            .and_then(|v| ConfigValue::from_toml(source, v).ok());
+    let value = match parsed {
+        Some(parsed) => parsed,
+        None => ConfigValue::String(value.to_string(), source.to_path_buf()),
+    };
+    let file = try!(config::set_config(config, loc, key, value));
+    try!(config.shell().status("Updated",
+                               format!("`{}` in {}", key, file.display())));
+    Ok(())
+}
+
+fn print_value(key: &str, value: &ConfigValue) {
+    match *value {
+        ConfigValue::Table(ref table, _) => {
+            let table = table.iter().collect::<BTreeMap<_, _>>();
+            for (k, v) in table.into_iter() {
+                print_value(&format!("{}.{}", key, k), v);
+            }
+        }
+        ConfigValue::List(ref list, ref path) => {
+            // Lists are merged across files, so each item may have come from
+            // a different one.
+            let mut paths: Vec<&PathBuf> = Vec::new();
+            for &(_, ref p) in list.iter() {
+                if !paths.contains(&p) { paths.push(p); }
+            }
+            if paths.is_empty() { paths.push(path); }
+            let paths = paths.iter().map(|p| p.display().to_string())
+                             .collect::<Vec<_>>();
+            println!("{} = {}  # {}", key, value.clone().into_toml(),
+                     paths.join(", "));
+        }
+        // Registry tokens, such as those `cargo login` keeps in the
+        // credentials file, are secrets and are never printed.
+        _ if key == "token" || key.ends_with(".token") => {
+            println!("{} = \"<redacted>\"  # {}", key,
+                     value.definition_path().display());
+        }
+        _ => {
+            println!("{} = {}  # {}", key, value.clone().into_toml(),
+                     value.definition_path().display());
+        }
+    }
+}
@@ -1,11 +1,11 @@
 use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use core::{Package, PackageIdSpec};
+use core::{Package, Target};
 use ops;
-use util::CargoResult;
+use util::{self, CargoResult, paths};
 
 pub struct DocOptions<'a> {
     pub open_result: bool,
@@ -14,44 +14,33 @@ pub struct DocOptions<'a> {
 
 pub fn doc(manifest_path: &Path,
            options: &DocOptions) -> CargoResult<()> {
-    let package = try!(Package::for_path(manifest_path, options.compile_opts.config));
+    let config = options.compile_opts.config;
+    let package = try!(Package::for_path(manifest_path, config));
 
-    let mut lib_names = HashSet::new();
-    let mut bin_names = HashSet::new();
-    if options.compile_opts.spec.len() == 0 {
-        for target in package.targets().iter().filter(|t| t.documented()) {
-            if target.is_lib() {
-                assert!(lib_names.insert(target.crate_name()));
-            } else {
-                assert!(bin_names.insert(target.crate_name()));
-            }
-        }
-        for bin in bin_names.iter() {
-            if lib_names.contains(bin) {
-                bail!("cannot document a package where a library and a binary \
-                       have the same name. Consider renaming one or marking \
-                       the target as `doc = false`")
-            }
-        }
-    }
+    let compilation = try!(ops::compile(manifest_path, &options.compile_opts));
+    let mut docs = compilation.docs.iter().collect::<Vec<_>>();
+    docs.sort_by(|a, b| {
+        (a.1.crate_name(), a.1.is_bin()).cmp(&(b.1.crate_name(), b.1.is_bin()))
+    });
+    let mut seen = HashSet::new();
+    docs.retain(|doc| seen.insert(doc.2.clone()));
 
-    try!(ops::compile(manifest_path, &options.compile_opts));
+    let mut doc_dir = config.target_dir(&package);
+    if let Some(triple) = options.compile_opts.target {
+        doc_dir.push(triple);
+    }
+    doc_dir.push("doc");
+    let index = doc_dir.join("index.html");
+    if docs.len() > 0 {
+        try!(write_index(&index, &doc_dir, &docs));
+    }
 
     if options.open_result {
-        let name = if options.compile_opts.spec.len() > 1 {
-            bail!("Passing multiple packages and `open` is not supported")
-        } else if options.compile_opts.spec.len() == 1 {
-            try!(PackageIdSpec::parse(&options.compile_opts.spec[0]))
-                                             .name().replace("-", "_").to_string()
-        } else {
-            match lib_names.iter().chain(bin_names.iter()).nth(0) {
-                Some(s) => s.to_string(),
-                None => return Ok(())
-            }
+        let path = match docs.len() {
+            0 => return Ok(()),
+            1 => docs[0].2.clone(),
+            _ => index,
         };
-
-        let target_dir = options.compile_opts.config.target_dir(&package);
-        let path = target_dir.join("doc").join(&name).join("index.html");
         if fs::metadata(&path).is_ok() {
             open_docs(&path);
         }
@@ -60,6 +49,30 @@ pub fn doc(manifest_path: &Path,
     Ok(())
 }
 
+/// Write an `index.html` to `doc_dir` linking to the documentation of every
+/// crate in `docs`.
+fn write_index(index: &Path, doc_dir: &Path,
+               docs: &[&(Package, Target, PathBuf)]) -> CargoResult<()> {
+    let mut html = "<!DOCTYPE html>\n<html>\n<head>\n\
+                    <meta charset=\"utf-8\">\n\
+                    <title>Crates</title>\n\
+                    </head>\n<body>\n<h1>Crates</h1>\n<ul>\n".to_string();
+    for &&(ref pkg, ref target, ref path) in docs.iter() {
+        let href = match util::without_prefix(path, doc_dir) {
+            Some(path) => path.components().map(|c| {
+                c.as_os_str().to_string_lossy().into_owned()
+            }).collect::<Vec<_>>().join("/"),
+            None => continue,
+        };
+        let kind = if target.is_lib() {"lib"} else {"bin"};
+        html.push_str(&format!("<li><a href=\"{}\">{}</a> ({} of {} {})</li>\n",
+                               href, target.crate_name(), kind, pkg.name(),
+                               pkg.version()));
+    }
+    html.push_str("</ul>\n</body>\n</html>\n");
+    paths::write(index, html.as_bytes())
+}
+
 #[cfg(not(any(target_os = "windows", target_os = "macos")))]
 fn open_docs(path: &Path) {
     // trying xdg-open
@@ -0,0 +1,63 @@
+use std::fs;
+
+use filetime::FileTime;
+use time;
+
+use util::{CargoResult, ChainError, Config, human};
+
+pub struct GitGcOptions<'a> {
+    pub config: &'a Config,
+    /// Databases not used for this many days are removed
+    pub max_age_days: u64,
+    pub dry_run: bool,
+}
+
+/// Executes `cargo git-gc`.
+///
+/// Every git database under `Config::git_db_path` which hasn't been used by a
+/// build within `max_age_days` is removed along with all of its checkouts.
+/// They'll simply be cloned again if a project needs them later on.
+pub fn git_gc(opts: &GitGcOptions) -> CargoResult<()> {
+    let config = opts.config;
+    let db_root = config.git_db_path();
+    let entries = match fs::read_dir(&db_root) {
+        Ok(entries) => entries,
+        Err(..) => return Ok(()),
+    };
+    let mut dbs = Vec::new();
+    for entry in entries {
+        let entry = try!(entry);
+        if try!(entry.file_type()).is_dir() {
+            dbs.push(entry.path());
+        }
+    }
+    dbs.sort();
+
+    let now = time::now_utc().to_timespec().sec as u64;
+    let max_age = opts.max_age_days * 24 * 60 * 60;
+    let cutoff = if now > max_age {now - max_age} else {0};
+
+    for db in dbs.iter() {
+        let meta = try!(fs::metadata(db));
+        let mtime = FileTime::from_last_modification_time(&meta);
+        if mtime.seconds_relative_to_1970() >= cutoff { continue }
+
+        let checkouts = config.git_checkout_path().join(db.file_name().unwrap());
+        if opts.dry_run {
+            try!(config.shell().status("Would remove", db.display()));
+            continue
+        }
+        try!(config.shell().status("Removing", db.display()));
+
+        try!(fs::remove_dir_all(db).chain_error(|| {
+            human(format!("failed to remove git database {}", db.display()))
+        }));
+        if fs::metadata(&checkouts).is_ok() {
+            try!(fs::remove_dir_all(&checkouts).chain_error(|| {
+                human(format!("failed to remove git checkouts {}",
+                              checkouts.display()))
+            }));
+        }
+    }
+    Ok(())
+}
@@ -209,9 +209,13 @@ fn check_overwrites(dst: &Path,
                 try!(check(target.name()));
             }
         }
-        CompileFilter::Only { bins, examples, .. } => {
-            for bin in bins.iter().chain(examples) {
-                try!(check(bin));
+        CompileFilter::Only { ref bins, ref examples, .. } => {
+            let targets = pkg.targets().iter().filter(|t| {
+                (t.is_bin() && bins.matches(t)) ||
+                    (t.is_example() && examples.matches(t))
+            });
+            for target in targets {
+                try!(check(target.name()));
             }
         }
     }
@@ -326,10 +330,9 @@ pub fn uninstall(root: Option<&str>,
 }
 
 fn resolve_root(flag: Option<&str>, config: &Config) -> CargoResult<PathBuf> {
+    // `install.root` may also come from `CARGO_INSTALL_ROOT`
     let config_root = try!(config.get_string("install.root"));
     Ok(flag.map(PathBuf::from).or_else(|| {
-        env::var_os("CARGO_INSTALL_ROOT").map(PathBuf::from)
-    }).or_else(|| {
         config_root.clone().map(|(v, _)| PathBuf::from(v))
     }).unwrap_or_else(|| {
         config.home().to_owned()
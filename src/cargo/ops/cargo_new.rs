@@ -1,7 +1,7 @@
 use std::env;
 use std::fs;
 use std::io::prelude::*;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use rustc_serialize::{Decodable, Decoder};
 
@@ -9,8 +9,10 @@ use git2::Config as GitConfig;
 
 use term::color::BLACK;
 
+use core::{GitReference, SourceId};
+use sources::GitSource;
 use util::{GitRepo, HgRepo, CargoResult, human, ChainError, internal};
-use util::{Config, paths};
+use util::{Config, ToUrl, normalize_path, paths};
 
 use toml;
 
@@ -22,6 +24,9 @@ pub struct NewOptions<'a> {
     pub bin: bool,
     pub path: &'a str,
     pub name: Option<&'a str>,
+    /// A directory or git URL holding a skeleton to create the project from
+    /// instead of the built-in one
+    pub template: Option<&'a str>,
 }
 
 impl Decodable for VersionControl {
@@ -49,14 +54,46 @@ pub fn new(opts: NewOptions, config: &Config) -> CargoResult<()> {
     if fs::metadata(&path).is_ok() {
         bail!("destination `{}` already exists", path.display())
     }
-    let name = match opts.name {
+    let name = try!(package_name(&path, opts.name, opts.bin, config));
+    mk(config, &path, name, opts.bin, &opts).chain_error(|| {
+        human(format!("Failed to create project `{}` at `{}`",
+                      name, path.display()))
+    })
+}
+
+/// Turns the directory at `opts.path`, which may already contain sources and
+/// a repository, into a package.
+///
+/// An existing `src/main.rs` or `src/lib.rs` is left alone and picked up as
+/// the package's binary or library, and no new repository is created if the
+/// directory is already under version control.
+pub fn init(opts: NewOptions, config: &Config) -> CargoResult<()> {
+    let path = normalize_path(&config.cwd().join(opts.path));
+    if fs::metadata(&path.join("Cargo.toml")).is_ok() {
+        bail!("`cargo init` cannot be run on existing Cargo projects")
+    }
+    let has_bin = fs::metadata(&path.join("src/main.rs")).is_ok();
+    let has_lib = fs::metadata(&path.join("src/lib.rs")).is_ok();
+    let bin = opts.bin || (has_bin && !has_lib);
+
+    let name = try!(package_name(&path, opts.name, bin, config));
+    try!(fs::create_dir_all(&path));
+    mk(config, &path, name, bin, &opts).chain_error(|| {
+        human(format!("Failed to create project `{}` at `{}`",
+                      name, path.display()))
+    })
+}
+
+fn package_name<'a>(path: &'a Path, name: Option<&'a str>, bin: bool,
+                    config: &Config) -> CargoResult<&'a str> {
+    let name = match name {
         Some(name) => name,
         None => {
             let dir_name = try!(path.file_name().and_then(|s| s.to_str()).chain_error(|| {
                 human(&format!("cannot create a project with a non-unicode name: {:?}",
                                path.file_name().unwrap()))
             }));
-            if opts.bin {
+            if bin {
                 dir_name
             } else {
                 let new_name = strip_rust_affixes(dir_name);
@@ -75,10 +112,7 @@ pub fn new(opts: NewOptions, config: &Config) -> CargoResult<()> {
         if c == '_' || c == '-' { continue }
         bail!("Invalid character `{}` in crate name: `{}`", c, name)
     }
-    mk(config, &path, name, &opts).chain_error(|| {
-        human(format!("Failed to create project `{}` at `{}`",
-                      name, path.display()))
-    })
+    Ok(name)
 }
 
 fn strip_rust_affixes(name: &str) -> &str {
@@ -99,14 +133,10 @@ fn existing_vcs_repo(path: &Path, cwd: &Path) -> bool {
     GitRepo::discover(path, cwd).is_ok() || HgRepo::discover(path, cwd).is_ok()
 }
 
-fn mk(config: &Config, path: &Path, name: &str,
+fn mk(config: &Config, path: &Path, name: &str, bin: bool,
       opts: &NewOptions) -> CargoResult<()> {
     let cfg = try!(global_config(config));
-    let mut ignore = "target\n".to_string();
     let in_existing_vcs_repo = existing_vcs_repo(path.parent().unwrap(), config.cwd());
-    if !opts.bin {
-        ignore.push_str("Cargo.lock\n");
-    }
 
     let vcs = match (opts.version_control, cfg.version_control, in_existing_vcs_repo) {
         (None, None, false) => VersionControl::Git,
@@ -115,18 +145,25 @@ fn mk(config: &Config, path: &Path, name: &str,
         (_, _, true) => VersionControl::NoVcs,
     };
 
-    match vcs {
+    // `cargo init` may be run in a directory which already has a repository
+    // of its own, which only needs to learn to ignore the build output.
+    let vcs = match vcs {
+        VersionControl::Git | VersionControl::Hg
+            if fs::metadata(&path.join(".git")).is_ok() => VersionControl::Git,
+        VersionControl::Git | VersionControl::Hg
+            if fs::metadata(&path.join(".hg")).is_ok() => VersionControl::Hg,
         VersionControl::Git => {
             try!(GitRepo::init(path, config.cwd()));
-            try!(paths::write(&path.join(".gitignore"), ignore.as_bytes()));
-        },
+            VersionControl::Git
+        }
         VersionControl::Hg => {
             try!(HgRepo::init(path, config.cwd()));
-            try!(paths::write(&path.join(".hgignore"), ignore.as_bytes()));
-        },
+            VersionControl::Hg
+        }
         VersionControl::NoVcs => {
-            try!(fs::create_dir(path));
-        },
+            try!(fs::create_dir_all(path));
+            VersionControl::NoVcs
+        }
     };
 
     let (author_name, email) = try!(discover_author());
@@ -139,7 +176,36 @@ fn mk(config: &Config, path: &Path, name: &str,
         (Some(name), None, _, None) |
         (None, None, name, None) => name,
     };
+    let authors = toml::Value::String(author).to_string();
+
+    match opts.template {
+        Some(template) => {
+            try!(render_template(config, template, path, name, &authors));
+        }
+        None => try!(write_default_files(path, name, bin, &authors)),
+    }
+
+    // A template decides for itself whether it's a binary, so look at what it
+    // wrote the same way `cargo init` looks at existing sources.
+    let bin = bin || (opts.template.is_some() &&
+                      fs::metadata(&path.join("src/main.rs")).is_ok() &&
+                      fs::metadata(&path.join("src/lib.rs")).is_err());
+    let mut ignore = vec!["target"];
+    if !bin {
+        ignore.push("Cargo.lock");
+    }
+
+    match vcs {
+        VersionControl::Git => try!(add_ignores(&path.join(".gitignore"), &ignore)),
+        VersionControl::Hg => try!(add_ignores(&path.join(".hgignore"), &ignore)),
+        VersionControl::NoVcs => {}
+    }
+
+    Ok(())
+}
 
+fn write_default_files(path: &Path, name: &str, bin: bool,
+                       authors: &str) -> CargoResult<()> {
     try!(paths::write(&path.join("Cargo.toml"), format!(
 r#"[package]
 name = "{}"
@@ -147,11 +213,16 @@ version = "0.1.0"
 authors = [{}]
 
 [dependencies]
-"#, name, toml::Value::String(author)).as_bytes()));
+"#, name, authors).as_bytes()));
 
-    try!(fs::create_dir(&path.join("src")));
+    // `cargo init` keeps whatever sources are already there
+    if fs::metadata(&path.join("src/main.rs")).is_ok() ||
+       fs::metadata(&path.join("src/lib.rs")).is_ok() {
+        return Ok(())
+    }
+    try!(fs::create_dir_all(&path.join("src")));
 
-    if opts.bin {
+    if bin {
         try!(paths::write(&path.join("src/main.rs"), b"\
 fn main() {
     println!(\"Hello, world!\");
@@ -168,6 +239,118 @@ fn it_works() {
     Ok(())
 }
 
+/// Add each of `ignore` to the ignore file at `file` unless it's already
+/// listed there.
+fn add_ignores(file: &Path, ignore: &[&str]) -> CargoResult<()> {
+    let mut contents = paths::read(file).unwrap_or(String::new());
+    let missing = ignore.iter().filter(|i| {
+        !contents.lines().any(|l| l.trim() == **i)
+    }).cloned().collect::<Vec<_>>();
+    if missing.is_empty() { return Ok(()) }
+
+    if !contents.is_empty() && !contents.ends_with("\n") {
+        contents.push('\n');
+    }
+    for line in missing {
+        contents.push_str(line);
+        contents.push('\n');
+    }
+    paths::write(file, contents.as_bytes())
+}
+
+/// Copy the skeleton at `template` into `dst`.
+///
+/// `{{name}}` is replaced with the package name and `{{authors}}` with the
+/// quoted author string, both in file contents and in file names. Files
+/// which already exist in `dst` are left alone.
+///
+/// A template which isn't a local directory is a git repository, either a
+/// URL or an scp-like `user@host:path`. Its `master` branch is used unless
+/// another one is named after a `#`, as in `URL#BRANCH`.
+fn render_template(config: &Config, template: &str, dst: &Path, name: &str,
+                   authors: &str) -> CargoResult<()> {
+    let render = |s: &str| {
+        s.replace("{{name}}", name).replace("{{authors}}", authors)
+    };
+
+    if let Some((url, branch)) = git_template(config, template) {
+        let url = try!(url.to_url().map_err(human));
+        let source_id = SourceId::for_git(&url, GitReference::Branch(branch));
+        let mut source = GitSource::new(&source_id, config);
+        let src = try!(source.checkout()).to_path_buf();
+        try!(copy_template(&src, dst, &render));
+    } else {
+        let src = config.cwd().join(template);
+        if !fs::metadata(&src).map(|m| m.is_dir()).unwrap_or(false) {
+            bail!("template `{}` is not a directory", src.display())
+        }
+        try!(copy_template(&src, dst, &render));
+    }
+
+    if fs::metadata(&dst.join("Cargo.toml")).is_err() {
+        bail!("template `{}` does not contain a `Cargo.toml`", template)
+    }
+    Ok(())
+}
+
+/// The URL and branch of `template` if it names a git repository rather than
+/// a local directory.
+fn git_template(config: &Config, template: &str) -> Option<(String, String)> {
+    if fs::metadata(&config.cwd().join(template)).is_ok() {
+        return None
+    }
+    let (location, branch) = match template.rfind('#') {
+        Some(i) => (&template[..i], template[i + 1..].to_string()),
+        None => (template, "master".to_string()),
+    };
+    if location.contains("://") {
+        return Some((location.to_string(), branch))
+    }
+    // `git@github.com:user/repo` is git's shorthand for an ssh URL. A drive
+    // letter, as in `C:\skel`, isn't a host though.
+    match location.find(':') {
+        Some(i) if i > 1 && !location[..i].contains('/') &&
+                   !location[..i].contains('\\') => {
+            let path = location[i + 1..].trim_left_matches('/');
+            Some((format!("ssh://{}/{}", &location[..i], path), branch))
+        }
+        _ => None,
+    }
+}
+
+fn copy_template(src: &Path, dst: &Path,
+                 render: &Fn(&str) -> String) -> CargoResult<()> {
+    for entry in try!(fs::read_dir(src)) {
+        let entry = try!(entry);
+        let file_name = entry.file_name();
+        let file_name = match file_name.to_str() {
+            Some(".git") => continue,
+            Some(s) => PathBuf::from(render(s)),
+            None => PathBuf::from(&file_name),
+        };
+        let dst = dst.join(&file_name);
+
+        if try!(entry.file_type()).is_dir() {
+            try!(fs::create_dir_all(&dst));
+            try!(copy_template(&entry.path(), &dst, render));
+            continue
+        }
+        if fs::metadata(&dst).is_ok() { continue }
+
+        let mut contents = Vec::new();
+        try!(try!(fs::File::open(entry.path())).read_to_end(&mut contents));
+        // Anything which isn't text, such as an image, is copied as it is
+        let contents = match String::from_utf8(contents) {
+            Ok(s) => render(&s).into_bytes(),
+            Err(e) => e.into_bytes(),
+        };
+        try!(paths::write(&dst, &contents).chain_error(|| {
+            human(format!("failed to copy template file to `{}`", dst.display()))
+        }));
+    }
+    Ok(())
+}
+
 fn discover_author() -> CargoResult<(String, Option<String>)> {
     let git_config = GitConfig::open_default().ok();
     let git_config = git_config.as_ref();
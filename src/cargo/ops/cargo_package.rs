@@ -3,11 +3,11 @@ use std::fs::{self, File};
 use std::path::{self, Path, PathBuf};
 
 use semver::VersionReq;
-use tar::Archive;
+use tar::{Archive, Header};
 use flate2::{GzBuilder, Compression};
 use flate2::read::GzDecoder;
 
-use core::{SourceId, Package, PackageId};
+use core::Package;
 use core::dependency::Kind;
 use sources::PathSource;
 use util::{self, CargoResult, human, internal, ChainError, Config};
@@ -33,6 +33,12 @@ pub fn package(manifest_path: &Path,
                                             config));
     let pkg = try!(src.root_package());
 
+    if pkg.manifest().publish().map(|r| r.is_empty()).unwrap_or(false) {
+        bail!("`{}` cannot be packaged for publishing.\n`publish` is set to \
+               `false` or an empty list in Cargo.toml and prevents \
+               publishing.", pkg.name())
+    }
+
     if metadata {
         try!(check_metadata(&pkg, config));
     }
@@ -165,6 +171,29 @@ fn tar(pkg: &Package, src: &PathSource, config: &Config,
         }));
         let path = format!("{}-{}{}{}", pkg.name(), pkg.version(),
                            path::MAIN_SEPARATOR, relative);
+
+        // The manifest is archived twice: the user's original is kept as
+        // `Cargo.toml.orig` while `Cargo.toml` itself is rewritten into a
+        // form which makes sense outside of the local checkout.
+        if relative == "Cargo.toml" {
+            let orig = format!("{}.orig", path);
+            try!(ar.append_file(&orig, &mut file).chain_error(|| {
+                internal(format!("could not archive source file `{}`",
+                                 relative))
+            }));
+            let contents = try!(normalized_manifest(pkg, config));
+            let mut header = Header::new();
+            try!(header.set_path(&path));
+            header.set_mode(0o644);
+            header.set_size(contents.len() as u64);
+            header.set_cksum();
+            try!(ar.append(&header, &mut contents.as_bytes()).chain_error(|| {
+                internal(format!("could not archive source file `{}`",
+                                 relative))
+            }));
+            continue
+        }
+
         try!(ar.append_file(&path, &mut file).chain_error(|| {
             internal(format!("could not archive source file `{}`", relative))
         }));
@@ -173,6 +202,12 @@ fn tar(pkg: &Package, src: &PathSource, config: &Config,
     Ok(())
 }
 
+fn normalized_manifest(pkg: &Package, config: &Config) -> CargoResult<String> {
+    let mut contents = String::new();
+    try!(try!(File::open(pkg.manifest_path())).read_to_string(&mut contents));
+    util::toml::normalize_manifest(&contents, pkg.manifest_path(), config)
+}
+
 fn run_verify(config: &Config, pkg: &Package, tar: &Path)
               -> CargoResult<()> {
     try!(config.shell().status("Verifying", pkg));
@@ -187,27 +222,11 @@ fn run_verify(config: &Config, pkg: &Package, tar: &Path)
     try!(archive.unpack(dst.parent().unwrap()));
     let manifest_path = dst.join("Cargo.toml");
 
-    // When packages are uploaded to the registry, all path dependencies are
-    // implicitly converted to registry-based dependencies, so we rewrite those
-    // dependencies here.
-    //
-    // We also make sure to point all paths at `dst` instead of the previous
-    // location that the package was originally read from. In locking the
-    // `SourceId` we're telling it that the corresponding `PathSource` will be
-    // considered updated and we won't actually read any packages.
-    let registry = try!(SourceId::for_central(config));
-    let precise = Some("locked".to_string());
-    let new_src = try!(SourceId::for_path(&dst)).with_precise(precise);
-    let new_pkgid = try!(PackageId::new(pkg.name(), pkg.version(), &new_src));
-    let new_summary = pkg.summary().clone().map_dependencies(|d| {
-        if !d.source_id().is_path() { return d }
-        d.clone_inner().set_source_id(registry.clone()).into_dependency()
-    });
-    let mut new_manifest = pkg.manifest().clone();
-    new_manifest.set_summary(new_summary.override_id(new_pkgid));
-    let new_pkg = Package::new(new_manifest, &manifest_path);
-
-    // Now that we've rewritten all our path dependencies, compile it!
+    // Build from the normalized manifest which was placed in the tarball, so
+    // any path dependencies are resolved from the registry just as they would
+    // be for someone downloading the package.
+    let new_pkg = try!(Package::for_path(&manifest_path, config));
+
     try!(ops::compile_pkg(&new_pkg, None, &ops::CompileOptions {
         config: config,
         jobs: None,
@@ -41,7 +41,28 @@ pub fn run(manifest_path: &Path,
     }
 
     let compile = try!(ops::compile(manifest_path, options));
-    let exe = &compile.binaries[0];
+    let exe = match compile.binaries.first() {
+        Some(exe) => exe,
+        // the only bin was skipped because of its `required-features`
+        None => {
+            let bin = root.manifest().targets().iter().find(|t| t.is_bin());
+            let required = bin.and_then(|t| t.required_features());
+            let missing = required.map(|r| {
+                r.iter().filter(|f| {
+                    !compile.cfgs.contains(&format!("feature=\"{}\"", f))
+                }).map(|f| &f[..]).collect::<Vec<_>>()
+            }).unwrap_or(Vec::new());
+            match bin {
+                Some(bin) if !missing.is_empty() => {
+                    bail!("target `{}` requires the features: `{}`\n\
+                           Consider enabling them by passing e.g. \
+                           `--features=\"{}\"`", bin.name(),
+                          missing.join("`, `"), missing.join(" "))
+                }
+                _ => bail!("a bin target must be available for `cargo run`"),
+            }
+        }
+    };
     let exe = match util::without_prefix(&exe, config.cwd()) {
         Some(path) if path.file_name() == Some(path.as_os_str())
                    => Path::new(".").join(path).to_path_buf(),
@@ -17,11 +17,14 @@ pub struct Compilation<'cfg> {
     pub libraries: HashMap<PackageId, Vec<(Target, PathBuf)>>,
 
     /// An array of all tests created during this compilation.
-    pub tests: Vec<(Package, String, PathBuf)>,
+    pub tests: Vec<(Package, Target, PathBuf)>,
 
     /// An array of all binaries created.
     pub binaries: Vec<PathBuf>,
 
+    /// Every crate documented, with the path of its `index.html`.
+    pub docs: Vec<(Package, Target, PathBuf)>,
+
     /// All directires for the output of native build commands.
     ///
     /// This is currently used to drive some entries which are added to the
@@ -44,6 +47,10 @@ pub struct Compilation<'cfg> {
     /// Features enabled during this compilation.
     pub cfgs: HashSet<String>,
 
+    /// The `target.$triple.runner` to launch executables built for the
+    /// target through, such as an emulator when cross compiling.
+    pub target_runner: Option<(PathBuf, Vec<String>)>,
+
     config: &'cfg Config,
 }
 
@@ -56,9 +63,11 @@ impl<'cfg> Compilation<'cfg> {
             deps_output: PathBuf::from("/"),
             tests: Vec::new(),
             binaries: Vec::new(),
+            docs: Vec::new(),
             extra_env: HashMap::new(),
             to_doc_test: Vec::new(),
             cfgs: HashSet::new(),
+            target_runner: None,
             config: config,
         }
     }
@@ -75,9 +84,23 @@ impl<'cfg> Compilation<'cfg> {
     }
 
     /// See `process`.
+    ///
+    /// If a runner is configured for the target then the returned process
+    /// runs it, with `cmd` as its last argument so far.
     pub fn target_process<T: AsRef<OsStr>>(&self, cmd: T, pkg: &Package)
                                                -> CargoResult<CommandPrototype> {
-        self.process(CommandType::Target(cmd.as_ref().to_os_string()), pkg)
+        match self.target_runner {
+            Some((ref runner, ref args)) => {
+                let ty = CommandType::Target(runner.as_os_str().to_os_string());
+                let mut p = try!(self.process(ty, pkg));
+                p.args(args).arg(cmd);
+                Ok(p)
+            }
+            None => {
+                self.process(CommandType::Target(cmd.as_ref().to_os_string()),
+                             pkg)
+            }
+        }
     }
 
     /// See `process`.
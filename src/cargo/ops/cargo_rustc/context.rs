@@ -222,6 +222,14 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
             let mut metadata = unit.pkg.generate_metadata();
             metadata.mix(&format!("bin-{}", unit.target.name()));
             Some(metadata)
+        } else if unit.profile.check && !unit.target.is_lib() {
+            // rustc names the metadata of every crate type `lib*.rmeta`, so
+            // checking a binary, example or test with the same name as the
+            // library would otherwise clobber the library's metadata.
+            let mut metadata = unit.pkg.generate_metadata();
+            metadata.mix(&format!("check-{:?}-{}", unit.target.kind(),
+                                  unit.target.name()));
+            Some(metadata)
         } else if unit.pkg.package_id() == self.resolve.root() &&
                   !unit.profile.test {
             // If we're not building a unit test then the root package never
@@ -258,6 +266,11 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
 
         let mut ret = Vec::new();
         match *unit.target.kind() {
+            // Check builds stop after emitting metadata, which rustc always
+            // names `lib*.rmeta` regardless of the crate type.
+            _ if unit.profile.check => {
+                ret.push(format!("lib{}.rmeta", stem));
+            }
             TargetKind::Example | TargetKind::Bin | TargetKind::CustomBuild |
             TargetKind::Bench | TargetKind::Test => {
                 ret.push(format!("{}{}", stem, suffix));
@@ -337,7 +350,7 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
                 Unit {
                     pkg: pkg,
                     target: t,
-                    profile: self.lib_profile(id),
+                    profile: self.lib_or_check_profile(unit, t),
                     kind: unit.kind.for_target(t),
                 }
             })
@@ -486,7 +499,7 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
             Unit {
                 pkg: unit.pkg,
                 target: t,
-                profile: self.lib_profile(unit.pkg.package_id()),
+                profile: self.lib_or_check_profile(unit, t),
                 kind: unit.kind.for_target(t),
             }
         })
@@ -547,6 +560,24 @@ impl<'a, 'cfg> Context<'a, 'cfg> {
         }
     }
 
+    /// Returns the profile used to compile `target` as a dependency of `unit`.
+    ///
+    /// Dependencies of a check build are only checked as well, with the
+    /// exception of anything that must actually run on the host (build
+    /// scripts and plugins) which still needs real codegen.
+    fn lib_or_check_profile(&self, unit: &Unit, target: &Target)
+                            -> &'a Profile {
+        if unit.profile.check && !target.for_host() {
+            if self.build_config.release {
+                &self.profiles.check_release
+            } else {
+                &self.profiles.check
+            }
+        } else {
+            self.lib_profile(unit.pkg.package_id())
+        }
+    }
+
     pub fn build_script_profile(&self, _pkg: &PackageId) -> &'a Profile {
         // TODO: should build scripts always be built with a dev
         //       profile? How is this controlled at the CLI layer?
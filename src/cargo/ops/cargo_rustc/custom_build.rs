@@ -25,6 +25,19 @@ pub struct BuildOutput {
     pub cfgs: Vec<String>,
     /// Metadata to pass to the immediate dependencies
     pub metadata: Vec<(String, String)>,
+    /// Environment variables set when compiling the package
+    pub env: Vec<(String, String)>,
+    /// Extra arguments to pass to the linker when linking the final artifacts
+    /// of the package
+    pub link_args: Vec<String>,
+    /// Warnings to display to the user once the script has finished
+    pub warnings: Vec<String>,
+    /// Files, relative to the package root, whose modification should cause
+    /// the build script to be rerun
+    pub rerun_if_changed: Vec<String>,
+    /// Environment variables whose change should cause the build script to be
+    /// rerun
+    pub rerun_if_env_changed: Vec<String>,
 }
 
 pub type BuildMap = HashMap<(PackageId, Kind), BuildOutput>;
@@ -249,6 +262,11 @@ impl BuildOutput {
         let mut library_links = Vec::new();
         let mut cfgs = Vec::new();
         let mut metadata = Vec::new();
+        let mut env = Vec::new();
+        let mut link_args = Vec::new();
+        let mut warnings = Vec::new();
+        let mut rerun_if_changed = Vec::new();
+        let mut rerun_if_env_changed = Vec::new();
         let whence = format!("build script of `{}`", pkg_name);
 
         for line in input.lines() {
@@ -283,6 +301,15 @@ impl BuildOutput {
                 "rustc-link-lib" => library_links.push(value.to_string()),
                 "rustc-link-search" => library_paths.push(PathBuf::from(value)),
                 "rustc-cfg" => cfgs.push(value.to_string()),
+                "rustc-env" => {
+                    env.push(try!(BuildOutput::parse_rustc_env(value, &whence)))
+                }
+                "rustc-link-arg" => link_args.push(value.to_string()),
+                "warning" => warnings.push(value.to_string()),
+                "rerun-if-changed" => rerun_if_changed.push(value.to_string()),
+                "rerun-if-env-changed" => {
+                    rerun_if_env_changed.push(value.to_string())
+                }
                 _ => metadata.push((key.to_string(), value.to_string())),
             }
         }
@@ -292,9 +319,34 @@ impl BuildOutput {
             library_links: library_links,
             cfgs: cfgs,
             metadata: metadata,
+            env: env,
+            link_args: link_args,
+            warnings: warnings,
+            rerun_if_changed: rerun_if_changed,
+            rerun_if_env_changed: rerun_if_env_changed,
         })
     }
 
+    /// Returns whether the script printed any `rerun-if-*` directives, in
+    /// which case those are the only inputs used to decide whether it needs
+    /// to be run again.
+    pub fn has_rerun_directives(&self) -> bool {
+        !self.rerun_if_changed.is_empty() ||
+            !self.rerun_if_env_changed.is_empty()
+    }
+
+    pub fn parse_rustc_env(value: &str, whence: &str)
+                           -> CargoResult<(String, String)> {
+        let mut iter = value.splitn(2, '=');
+        let name = iter.next();
+        let val = iter.next();
+        match (name, val) {
+            (Some(n), Some(v)) => Ok((n.to_string(), v.to_string())),
+            _ => bail!("Variable rustc-env has no value in {}: {}",
+                       whence, value),
+        }
+    }
+
     pub fn parse_rustc_flags(value: &str, whence: &str)
                              -> CargoResult<(Vec<PathBuf>, Vec<String>)> {
         let value = value.trim();
@@ -1,3 +1,4 @@
+use std::env;
 use std::fs::{self, File, OpenOptions};
 use std::hash::{Hash, Hasher, SipHasher};
 use std::io::prelude::*;
@@ -15,6 +16,7 @@ use util::paths;
 
 use super::job::Work;
 use super::context::{Context, Unit};
+use super::custom_build::BuildOutput;
 
 /// A tuple result of the `prepare_foo` functions in this module.
 ///
@@ -97,7 +99,7 @@ pub struct Fingerprint {
     target: u64,
     profile: u64,
     deps: Vec<(String, Arc<Fingerprint>)>,
-    local: LocalFingerprint,
+    local: Vec<LocalFingerprint>,
     resolved: Mutex<Option<u64>>,
 }
 
@@ -105,6 +107,7 @@ pub struct Fingerprint {
 enum LocalFingerprint {
     Precalculated(String),
     MtimeBased(MtimeSlot, PathBuf),
+    EnvBased(String, Option<String>),
 }
 
 struct MtimeSlot(Mutex<Option<FileTime>>);
@@ -121,18 +124,24 @@ impl Fingerprint {
         self.features.hash(&mut s);
         self.target.hash(&mut s);
         self.profile.hash(&mut s);
-        match self.local {
-            LocalFingerprint::MtimeBased(ref slot, ref path) => {
-                let mut slot = slot.0.lock().unwrap();
-                if force || slot.is_none() {
-                    let meta = try!(fs::metadata(path).chain_error(|| {
-                        internal(format!("failed to stat {:?}", path))
-                    }));
-                    *slot = Some(FileTime::from_last_modification_time(&meta));
+        for local in self.local.iter() {
+            match *local {
+                LocalFingerprint::MtimeBased(ref slot, ref path) => {
+                    let mut slot = slot.0.lock().unwrap();
+                    if force || slot.is_none() {
+                        let meta = try!(fs::metadata(path).chain_error(|| {
+                            internal(format!("failed to stat {:?}", path))
+                        }));
+                        *slot = Some(FileTime::from_last_modification_time(&meta));
+                    }
+                    slot.hash(&mut s);
+                }
+                LocalFingerprint::Precalculated(ref p) => p.hash(&mut s),
+                LocalFingerprint::EnvBased(ref key, ref val) => {
+                    key.hash(&mut s);
+                    val.hash(&mut s);
                 }
-                slot.hash(&mut s);
             }
-            LocalFingerprint::Precalculated(ref p) => p.hash(&mut s),
         }
 
         for &(_, ref dep) in self.deps.iter() {
@@ -157,26 +166,44 @@ impl Fingerprint {
         if self.profile != old.profile {
             return Err(internal("profile configuration has changed"))
         }
-        match (&self.local, &old.local) {
-            (&LocalFingerprint::Precalculated(ref a),
-             &LocalFingerprint::Precalculated(ref b)) => {
-                if a != b {
-                    return Err(internal(format!("precalculated components have \
-                                                 changed: {} != {}", a, b)))
+        if self.local.len() != old.local.len() {
+            return Err(internal("local fingerprint length has changed"))
+        }
+        for (new, old) in self.local.iter().zip(old.local.iter()) {
+            match (new, old) {
+                (&LocalFingerprint::Precalculated(ref a),
+                 &LocalFingerprint::Precalculated(ref b)) => {
+                    if a != b {
+                        return Err(internal(format!("precalculated components \
+                                                     have changed: {} != {}",
+                                                    a, b)))
+                    }
                 }
-            }
-            (&LocalFingerprint::MtimeBased(ref a, ref ap),
-             &LocalFingerprint::MtimeBased(ref b, ref bp)) => {
-                let a = a.0.lock().unwrap();
-                let b = b.0.lock().unwrap();
-                if *a != *b {
-                    return Err(internal(format!("mtime based components have \
-                                                 changed: {:?} != {:?}, paths \
-                                                 are {:?} and {:?}",
-                                                *a, *b, ap, bp)))
+                (&LocalFingerprint::MtimeBased(ref a, ref ap),
+                 &LocalFingerprint::MtimeBased(ref b, ref bp)) => {
+                    let a = a.0.lock().unwrap();
+                    let b = b.0.lock().unwrap();
+                    if *a != *b {
+                        return Err(internal(format!("mtime based components \
+                                                     have changed: {:?} != {:?}, \
+                                                     paths are {:?} and {:?}",
+                                                    *a, *b, ap, bp)))
+                    }
+                }
+                (&LocalFingerprint::EnvBased(ref akey, ref avalue),
+                 &LocalFingerprint::EnvBased(ref bkey, ref bvalue)) => {
+                    if *akey != *bkey {
+                        return Err(internal(format!("env vars changed: {} != {}",
+                                                    akey, bkey)))
+                    }
+                    if *avalue != *bvalue {
+                        return Err(internal(format!("env var `{}` changed: \
+                                                     previously {:?} now {:?}",
+                                                    akey, bvalue, avalue)))
+                    }
                 }
+                _ => return Err(internal("local fingerprint type has changed")),
             }
-            _ => return Err(internal("local fingerprint type has changed")),
         }
 
         if self.deps.len() != old.deps.len() {
@@ -234,7 +261,7 @@ impl Decodable for Fingerprint {
                             rustc: 0,
                             target: 0,
                             profile: 0,
-                            local: LocalFingerprint::Precalculated(String::new()),
+                            local: vec![LocalFingerprint::Precalculated(String::new())],
                             features: String::new(),
                             deps: Vec::new(),
                             resolved: Mutex::new(Some(resolved)),
@@ -327,6 +354,7 @@ fn calculate<'a, 'cfg>(cx: &mut Context<'a, 'cfg>, unit: &Unit<'a>)
         let fingerprint = try!(calculate_pkg_fingerprint(cx, unit.pkg));
         LocalFingerprint::Precalculated(fingerprint)
     };
+    let local = vec![local];
     let mut deps = deps;
     deps.sort_by(|&(ref a, _), &(ref b, _)| a.cmp(b));
     let fingerprint = Arc::new(Fingerprint {
@@ -367,8 +395,11 @@ fn use_dep_info(unit: &Unit) -> bool {
 /// 3. A build command must itself provide a dep-info-like file stating how it
 ///    should be considered dirty or not.
 ///
-/// The currently implemented solution is option (1), although it is planned to
-/// migrate to option (2) in the near future.
+/// Option (1) is the default, but a build script can opt in to option (3) by
+/// printing `cargo:rerun-if-changed=PATH` and `cargo:rerun-if-env-changed=VAR`
+/// lines. Once it has done so, the files and environment variables it listed
+/// during its last execution are the only inputs considered, and the rest of
+/// the package is ignored.
 pub fn prepare_build_cmd(cx: &mut Context, unit: &Unit)
                          -> CargoResult<Preparation> {
     let _p = profile::start(format!("fingerprint build cmd: {}",
@@ -378,33 +409,147 @@ pub fn prepare_build_cmd(cx: &mut Context, unit: &Unit)
 
     debug!("fingerprint at: {}", loc.display());
 
+    let output_file = cx.layout(unit.pkg, unit.kind).build(unit.pkg)
+                        .join("output");
+    let key = (unit.pkg.package_id().clone(), unit.kind);
+
     // If this build script execution has been overridden, then the fingerprint
     // is just a hash of what it was overridden with. Otherwise the fingerprint
-    // is that of the entire package itself as we just consider everything as
-    // input to the build script.
-    let new_fingerprint = {
+    // is derived from what the script said it depends on the last time it ran,
+    // falling back to the entire package if it never said anything.
+    let overridden = {
         let state = cx.build_state.outputs.lock().unwrap();
-        match state.get(&(unit.pkg.package_id().clone(), unit.kind)) {
-            Some(output) => {
-                format!("overridden build state with hash: {}",
-                        util::hash_u64(output))
+        state.get(&key).map(|output| {
+            format!("overridden build state with hash: {}",
+                    util::hash_u64(output))
+        })
+    };
+    let local = match overridden {
+        Some(ref s) => vec![LocalFingerprint::Precalculated(s.clone())],
+        None => {
+            let previous = previous_build_output(&output_file, unit);
+            match previous {
+                Some(ref output) if output.has_rerun_directives() => {
+                    let local = try!(build_script_local_fingerprints(
+                        output, &output_file, unit.pkg.root()));
+                    // As with dep-info files, remove the output of a stale
+                    // build script so that `resolve()` can't find it and the
+                    // script is run again.
+                    let stale = local.iter().any(|l| {
+                        match *l {
+                            LocalFingerprint::MtimeBased(ref slot, _) => {
+                                slot.0.lock().unwrap().is_none()
+                            }
+                            _ => false,
+                        }
+                    });
+                    if stale {
+                        let _ = fs::remove_file(&output_file);
+                    }
+                    local
+                }
+                _ => {
+                    let s = try!(calculate_pkg_fingerprint(cx, unit.pkg));
+                    vec![LocalFingerprint::Precalculated(s)]
+                }
             }
-            None => try!(calculate_pkg_fingerprint(cx, unit.pkg)),
         }
     };
-    let new_fingerprint = Arc::new(Fingerprint {
+    let new_fingerprint = Arc::new(build_cmd_fingerprint(local));
+
+    let compare = compare_old_fingerprint(&loc, &new_fingerprint);
+    log_compare(unit, &compare);
+
+    if overridden.is_some() {
+        return Ok(prepare(compare.is_ok(), false, loc, new_fingerprint))
+    }
+
+    // The script may print a different set of `rerun-if-*` directives this
+    // time around, so once it has run we recalculate the fingerprint from
+    // its fresh output before writing it out.
+    let build_state = cx.build_state.clone();
+    let root = unit.pkg.root().to_path_buf();
+    let write_fingerprint = Work::new(move |_| {
+        let mut fingerprint = new_fingerprint;
+        if let Some(output) = build_state.outputs.lock().unwrap().get(&key) {
+            if output.has_rerun_directives() {
+                let local = try!(build_script_local_fingerprints(output,
+                                                                 &output_file,
+                                                                 &root));
+                fingerprint = Arc::new(build_cmd_fingerprint(local));
+            }
+        }
+        write_fingerprint(&loc, &fingerprint, false)
+    });
+    let freshness = if compare.is_ok() {Fresh} else {Dirty};
+    Ok((freshness, write_fingerprint, Work::noop()))
+}
+
+fn build_cmd_fingerprint(local: Vec<LocalFingerprint>) -> Fingerprint {
+    Fingerprint {
         rustc: 0,
         target: 0,
         profile: 0,
         features: String::new(),
         deps: Vec::new(),
-        local: LocalFingerprint::Precalculated(new_fingerprint),
+        local: local,
         resolved: Mutex::new(None),
-    });
+    }
+}
 
-    let compare = compare_old_fingerprint(&loc, &new_fingerprint);
-    log_compare(unit, &compare);
-    Ok(prepare(compare.is_ok(), false, loc, new_fingerprint))
+/// Loads the output of the last execution of a build script, if any.
+fn previous_build_output(output_file: &Path, unit: &Unit) -> Option<BuildOutput> {
+    let contents = match paths::read(output_file) {
+        Ok(contents) => contents,
+        Err(..) => return None,
+    };
+    BuildOutput::parse(&contents, &unit.pkg.to_string()).ok()
+}
+
+/// Calculates the local fingerprints of a build script which has emitted
+/// `rerun-if-changed` or `rerun-if-env-changed` directives.
+///
+/// Listed files are tracked by comparing their mtimes against the script's
+/// `output` file, in the same way that dep-info files are used for targets.
+fn build_script_local_fingerprints(output: &BuildOutput,
+                                   output_file: &Path,
+                                   pkg_root: &Path)
+                                   -> CargoResult<Vec<LocalFingerprint>> {
+    let mut local = Vec::new();
+    if !output.rerun_if_changed.is_empty() {
+        let mtime = try!(calculate_build_script_mtime(output_file, pkg_root,
+                                                      &output.rerun_if_changed));
+        local.push(LocalFingerprint::MtimeBased(MtimeSlot(Mutex::new(mtime)),
+                                                output_file.to_path_buf()));
+    }
+    for var in output.rerun_if_env_changed.iter() {
+        let val = env::var(var).ok();
+        local.push(LocalFingerprint::EnvBased(var.clone(), val));
+    }
+    Ok(local)
+}
+
+fn calculate_build_script_mtime(output_file: &Path,
+                                pkg_root: &Path,
+                                paths: &[String])
+                                -> CargoResult<Option<FileTime>> {
+    let meta = match fs::metadata(output_file) {
+        Ok(meta) => meta,
+        Err(..) => return Ok(None),
+    };
+    let mtime = FileTime::from_last_modification_time(&meta);
+    for file in paths {
+        let meta = match fs::metadata(pkg_root.join(file)) {
+            Ok(meta) => meta,
+            Err(..) => { info!("stale: {} -- missing", file); return Ok(None) }
+        };
+        let file_mtime = FileTime::from_last_modification_time(&meta);
+        if file_mtime > mtime {
+            info!("stale: {} -- {} vs {}", file, file_mtime, mtime);
+            return Ok(None)
+        }
+    }
+    Ok(Some(mtime))
 }
 
 /// Prepare work for when a package starts to build
@@ -428,24 +573,30 @@ fn prepare(is_fresh: bool,
            loc: PathBuf,
            fingerprint: Arc<Fingerprint>) -> Preparation {
     let write_fingerprint = Work::new(move |_| {
-        debug!("write fingerprint: {}", loc.display());
-        let hash = match fingerprint.resolve(true) {
-            Ok(e) => e,
-            Err(..) if allow_failure => return Ok(()),
-            Err(e) => return Err(e).chain_error(|| {
-                internal("failed to resolve a pending fingerprint")
-            })
-
-        };
-        try!(paths::write(&loc, util::to_hex(hash).as_bytes()));
-        try!(paths::write(&loc.with_extension("json"),
-                          json::encode(&fingerprint).unwrap().as_bytes()));
-        Ok(())
+        write_fingerprint(&loc, &fingerprint, allow_failure)
     });
 
     (if is_fresh {Fresh} else {Dirty}, write_fingerprint, Work::noop())
 }
 
+fn write_fingerprint(loc: &Path,
+                     fingerprint: &Fingerprint,
+                     allow_failure: bool) -> CargoResult<()> {
+    debug!("write fingerprint: {}", loc.display());
+    let hash = match fingerprint.resolve(true) {
+        Ok(e) => e,
+        Err(..) if allow_failure => return Ok(()),
+        Err(e) => return Err(e).chain_error(|| {
+            internal("failed to resolve a pending fingerprint")
+        })
+
+    };
+    try!(paths::write(loc, util::to_hex(hash).as_bytes()));
+    try!(paths::write(&loc.with_extension("json"),
+                      json::encode(fingerprint).unwrap().as_bytes()));
+    Ok(())
+}
+
 /// Return the (old, new) location for fingerprints for a package
 pub fn dir(cx: &Context, unit: &Unit) -> PathBuf {
     cx.layout(unit.pkg, unit.kind).proxy().fingerprint(unit.pkg)
@@ -563,6 +714,8 @@ fn filename(unit: &Unit) -> String {
         "test-"
     } else if unit.profile.doc {
         "doc-"
+    } else if unit.profile.check {
+        "check-"
     } else {
         ""
     };
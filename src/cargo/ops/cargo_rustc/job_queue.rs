@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 use std::collections::hash_map::HashMap;
 use std::fmt;
+use std::sync::Arc;
 use std::sync::mpsc::{channel, Sender, Receiver};
 
 use crossbeam::{self, Scope};
@@ -11,6 +12,7 @@ use util::{Config, DependencyQueue, Fresh, Dirty, Freshness};
 use util::{CargoResult, Dependency, profile, internal};
 
 use super::{Context, Kind, Unit};
+use super::custom_build::BuildState;
 use super::job::Job;
 
 /// A management structure of the entire dependency graph to compile.
@@ -28,6 +30,7 @@ pub struct JobQueue<'a> {
     compiled: HashSet<&'a PackageId>,
     documented: HashSet<&'a PackageId>,
     counts: HashMap<&'a PackageId, usize>,
+    build_state: Arc<BuildState>,
 }
 
 /// A helper structure for metadata about the state of a building package.
@@ -49,6 +52,7 @@ struct Key<'a> {
 
 struct Message<'a> {
     key: Key<'a>,
+    fresh: Freshness,
     result: CargoResult<()>,
 }
 
@@ -65,6 +69,7 @@ impl<'a> JobQueue<'a> {
             compiled: HashSet::new(),
             documented: HashSet::new(),
             counts: HashMap::new(),
+            build_state: cx.build_state.clone(),
         }
     }
 
@@ -134,6 +139,9 @@ impl<'a> JobQueue<'a> {
             self.active -= 1;
             match msg.result {
                 Ok(()) => {
+                    if msg.key.profile.run_custom_build && msg.fresh == Dirty {
+                        try!(self.emit_warnings(config, &msg.key));
+                    }
                     let state = self.pending.get_mut(&msg.key).unwrap();
                     state.amt -= 1;
                     if state.amt == 0 {
@@ -178,6 +186,7 @@ impl<'a> JobQueue<'a> {
         scope.spawn(move || {
             my_tx.send(Message {
                 key: key,
+                fresh: fresh,
                 result: job.run(fresh, desc_tx),
             }).unwrap();
         });
@@ -192,6 +201,29 @@ impl<'a> JobQueue<'a> {
         Ok(())
     }
 
+    /// Prints any warnings emitted by the build script that just finished
+    /// running for `key`.
+    ///
+    /// Warnings from packages which aren't local are only shown in verbose
+    /// mode, the same way that compiler warnings are capped for them.
+    fn emit_warnings(&self, config: &Config, key: &Key<'a>) -> CargoResult<()> {
+        let outputs = self.build_state.outputs.lock().unwrap();
+        let output = match outputs.get(&(key.pkg.clone(), key.kind)) {
+            Some(output) => output,
+            None => return Ok(()),
+        };
+        let local = key.pkg.source_id().is_path();
+        for warning in output.warnings.iter() {
+            let msg = format!("warning: {}", warning);
+            if local {
+                try!(config.shell().warn(&msg));
+            } else {
+                try!(config.shell().verbose(|c| c.warn(&msg)));
+            }
+        }
+        Ok(())
+    }
+
     // This isn't super trivial because we don't want to print loads and
     // loads of information to the console, but we also want to produce a
     // faithful representation of what's happening. This is somewhat nuanced
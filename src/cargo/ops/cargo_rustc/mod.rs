@@ -48,6 +48,9 @@ pub struct BuildConfig {
 pub struct TargetConfig {
     pub ar: Option<PathBuf>,
     pub linker: Option<PathBuf>,
+    /// A program, and its arguments, which executables built for this target
+    /// are run through
+    pub runner: Option<(PathBuf, Vec<String>)>,
     pub overrides: HashMap<String, BuildOutput>,
 }
 
@@ -119,7 +122,7 @@ pub fn compile_targets<'a, 'cfg: 'a>(pkg_targets: &'a PackagesToBuild<'a>,
             let dst = cx.out_dir(unit).join(filename);
             if unit.profile.test {
                 cx.compilation.tests.push((unit.pkg.clone(),
-                                           unit.target.name().to_string(),
+                                           unit.target.clone(),
                                            dst));
             } else if unit.target.is_bin() || unit.target.is_example() {
                 cx.compilation.binaries.push(dst);
@@ -148,6 +151,8 @@ pub fn compile_targets<'a, 'cfg: 'a>(pkg_targets: &'a PackagesToBuild<'a>,
         }
     }
 
+    cx.compilation.target_runner = cx.build_config.target.runner.clone();
+
     let root_pkg = root.package_id();
     if let Some(feats) = cx.resolve.features(root_pkg) {
         cx.compilation.cfgs.extend(feats.iter().map(|feat| {
@@ -159,6 +164,12 @@ pub fn compile_targets<'a, 'cfg: 'a>(pkg_targets: &'a PackagesToBuild<'a>,
         if pkg == root_pkg {
             cx.compilation.cfgs.extend(output.cfgs.iter().cloned());
         }
+        // Variables from `rustc-env` are also needed when running doctests,
+        // which are compiled later on.
+        for &(ref name, ref value) in output.env.iter() {
+            cx.compilation.extra_env.entry(pkg.clone()).or_insert(Vec::new())
+              .push((name.clone(), value.clone()));
+        }
         let any_dylib = output.library_links.iter().any(|l| {
             !l.starts_with("static=") && !l.starts_with("framework=")
         });
@@ -236,6 +247,11 @@ fn rustc(cx: &mut Context, unit: &Unit) -> CargoResult<Work> {
     // don't pass the `-l` flags.
     let pass_l_flag = unit.target.is_lib() ||
                       !unit.pkg.targets().iter().any(|t| t.is_lib());
+    // Linker arguments from the build script only apply to the artifacts
+    // which are actually linked, not to intermediate rlibs.
+    let pass_link_args = unit.target.rustc_crate_types().iter().any(|t| {
+        *t != "lib" && *t != "rlib"
+    });
     let do_rename = unit.target.allows_underscores() && !unit.profile.test;
     let real_name = unit.target.name().to_string();
     let crate_name = unit.target.crate_name();
@@ -259,7 +275,7 @@ fn rustc(cx: &mut Context, unit: &Unit) -> CargoResult<Work> {
         if let Some(build_deps) = build_deps {
             let build_state = build_state.outputs.lock().unwrap();
             try!(add_native_deps(&mut rustc, &build_state, &build_deps,
-                                 pass_l_flag, &current_id));
+                                 pass_l_flag, pass_link_args, &current_id));
             try!(add_plugin_deps(&mut rustc, &build_state, &build_deps));
         }
 
@@ -306,6 +322,7 @@ fn rustc(cx: &mut Context, unit: &Unit) -> CargoResult<Work> {
                        build_state: &BuildMap,
                        build_scripts: &BuildScripts,
                        pass_l_flag: bool,
+                       pass_link_args: bool,
                        current_id: &PackageId) -> CargoResult<()> {
         for key in build_scripts.to_link.iter() {
             let output = try!(build_state.get(key).chain_error(|| {
@@ -319,6 +336,14 @@ fn rustc(cx: &mut Context, unit: &Unit) -> CargoResult<Work> {
                 for cfg in &output.cfgs {
                     rustc.arg("--cfg").arg(cfg);
                 }
+                for &(ref name, ref value) in output.env.iter() {
+                    rustc.env(name, value);
+                }
+                if pass_link_args {
+                    for arg in output.link_args.iter() {
+                        rustc.arg("-C").arg(&format!("link-arg={}", arg));
+                    }
+                }
                 if pass_l_flag {
                     for name in output.library_links.iter() {
                         rustc.arg("-l").arg(name);
@@ -375,12 +400,12 @@ fn rustdoc(cx: &mut Context, unit: &Unit) -> CargoResult<Work> {
            .cwd(cx.config.cwd())
            .arg("--crate-name").arg(&unit.target.crate_name());
 
-    let mut doc_dir = cx.config.target_dir(cx.get_package(cx.resolve.root()));
     if let Some(target) = cx.requested_target() {
         rustdoc.arg("--target").arg(target);
-        doc_dir.push(target);
     }
-    doc_dir.push("doc");
+    let doc_dir = doc_dir(cx, unit);
+    let index = doc_dir.join(unit.target.crate_name()).join("index.html");
+    cx.compilation.docs.push((unit.pkg.clone(), unit.target.clone(), index));
 
     // Create the documentation directory ahead of time as rustdoc currently has
     // a bug where concurrent invocations will race to create this directory if
@@ -416,6 +441,9 @@ fn rustdoc(cx: &mut Context, unit: &Unit) -> CargoResult<Work> {
             for cfg in output.cfgs.iter() {
                 rustdoc.arg("--cfg").arg(cfg);
             }
+            for &(ref name, ref value) in output.env.iter() {
+                rustdoc.env(name, value);
+            }
         }
         desc_tx.send(rustdoc.to_string()).unwrap();
         exec_engine.exec(rustdoc).chain_error(|| {
@@ -424,6 +452,27 @@ fn rustdoc(cx: &mut Context, unit: &Unit) -> CargoResult<Work> {
     }))
 }
 
+/// The directory rustdoc is told to place the documentation for `unit` in.
+///
+/// Everything shares one directory so crates link to each other, except that
+/// a binary with the same name as its package's library goes in a `bin`
+/// directory within it to keep the two apart.
+fn doc_dir(cx: &Context, unit: &Unit) -> PathBuf {
+    let mut doc_dir = cx.config.target_dir(cx.get_package(cx.resolve.root()));
+    if let Some(target) = cx.requested_target() {
+        doc_dir.push(target);
+    }
+    doc_dir.push("doc");
+
+    let crate_name = unit.target.crate_name();
+    if unit.target.is_bin() && unit.pkg.targets().iter().any(|t| {
+        t.is_lib() && t.crate_name() == crate_name
+    }) {
+        doc_dir.push("bin");
+    }
+    doc_dir
+}
+
 // The path that we pass to rustc is actually fairly important because it will
 // show up in error messages and the like. For this reason we take a few moments
 // to ensure that something shows up pretty reasonably.
@@ -452,7 +501,7 @@ fn build_base_args(cx: &Context,
     let Profile {
         opt_level, lto, codegen_units, ref rustc_args, debuginfo,
         debug_assertions, rpath, test, doc: _doc, run_custom_build,
-        rustdoc_args: _,
+        rustdoc_args: _, check: _,
     } = *unit.profile;
     assert!(!run_custom_build);
 
@@ -537,7 +586,13 @@ fn build_plugin_args(cmd: &mut CommandPrototype, cx: &Context, unit: &Unit) {
     }
 
     cmd.arg("--out-dir").arg(&cx.out_dir(unit));
-    cmd.arg("--emit=dep-info,link");
+    // A check build only needs the crate metadata to be available to
+    // downstream crates, so codegen is skipped entirely.
+    if unit.profile.check {
+        cmd.arg("--emit=dep-info,metadata");
+    } else {
+        cmd.arg("--emit=dep-info,link");
+    }
 
     if unit.kind == Kind::Target {
         opt(cmd, "--target", "", cx.requested_target().map(|s| s.as_ref()));
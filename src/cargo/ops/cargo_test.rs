@@ -1,18 +1,110 @@
+use std::cmp;
 use std::ffi::{OsString, OsStr};
+use std::io::prelude::*;
+use std::io;
 use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
 
-use ops::{self, ExecEngine, ProcessEngine, Compilation};
-use util::{self, CargoResult, CargoTestError, ProcessError};
+use crossbeam;
+use term::color::BLACK;
+use time;
+
+use core::{Package, Target, TargetKind};
+use ops::{self, ExecEngine, ProcessEngine, Compilation, CommandPrototype};
+use ops::TestReport;
+use ops::test_report::{self, Outcome, Suite};
+use util::{self, CargoResult, CargoTestError, Config, ProcessError};
+use util::{ProcessBuilder, process_error};
 
 pub struct TestOptions<'a> {
     pub compile_opts: ops::CompileOptions<'a>,
     pub no_run: bool,
     pub no_fail_fast: bool,
+    /// Print the name of every test instead of running them
+    pub list: bool,
+    /// Only run tests whose name exactly matches a filter
+    pub exact: bool,
+    /// How many test binaries to run at once, overriding `test.jobs`
+    pub test_jobs: Option<u32>,
+    /// Where to write a report of every test's result, if anywhere
+    pub report: Option<TestReport>,
 }
 
+/// A test binary which failed, and the tests in it which did.
+struct Failure<'a> {
+    pkg: &'a Package,
+    /// The target holding the tests, or `None` for doc tests
+    target: Option<&'a Target>,
+    tests: Vec<String>,
+}
 
+/// Everything learned from running the tests, beyond whether they passed.
+struct TestResults<'a> {
+    failures: Vec<Failure<'a>>,
+    /// The results of each binary, only collected for `--report`
+    suites: Vec<Suite>,
+}
+
+/// A test binary, or rustdoc testing a library, waiting to be run.
+struct TestRun<'a> {
+    pkg: &'a Package,
+    /// The target holding the tests, or `None` for doc tests
+    target: Option<&'a Target>,
+    /// The path of the binary, or the name of the library for doc tests
+    name: String,
+    cmd: ProcessBuilder,
+}
+
+impl<'a> TestRun<'a> {
+    fn announce(&self, config: &Config) -> CargoResult<()> {
+        match self.target {
+            Some(..) => try!(config.shell().concise(|shell| {
+                shell.status("Running", &self.name)
+            })),
+            None => try!(config.shell().status("Doc-tests", &self.name)),
+        }
+        try!(config.shell().verbose(|shell| {
+            shell.status("Running", self.cmd.to_string())
+        }));
+        Ok(())
+    }
+
+    fn failure(self, tests: Vec<String>) -> Failure<'a> {
+        Failure { pkg: self.pkg, target: self.target, tests: tests }
+    }
+
+    /// The results of this run for the report. `finished` holds when the
+    /// result of each test was printed, if that was watched as it happened.
+    fn suite(&self, stdout: &[u8], start: u64,
+             finished: &[(String, u64)]) -> Suite {
+        let target = match self.target {
+            Some(target) => target_description(target),
+            None => format!("doc {}", self.name),
+        };
+        let stdout = String::from_utf8_lossy(stdout);
+        let mut cases = test_report::parse_output(&stdout);
+
+        // Each test is timed from when the previous result was printed, which
+        // is exact as long as the harness runs one test at a time.
+        let mut last = start;
+        for &(ref name, at) in finished.iter() {
+            if let Some(case) = cases.iter_mut().find(|c| c.name == *name) {
+                case.time = Some((at - last) as f64 / 1e9);
+            }
+            last = at;
+        }
+
+        Suite {
+            package: self.pkg.name().to_string(),
+            target: target,
+            time: (time::precise_time_ns() - start) as f64 / 1e9,
+            cases: cases,
+        }
+    }
+}
 
-#[allow(deprecated)] // connect => join in 1.3
 pub fn run_tests(manifest_path: &Path,
                  options: &TestOptions,
                  test_args: &[String]) -> CargoResult<Option<CargoTestError>> {
@@ -21,23 +113,31 @@ pub fn run_tests(manifest_path: &Path,
     if options.no_run {
         return Ok(None)
     }
-    let mut errors = try!(run_unit_tests(options, test_args, &compilation));
-
-    // If we have an error and want to fail fast, return
-    if errors.len() > 0 && !options.no_fail_fast {
-        return Ok(Some(CargoTestError::new(errors)))
+    if options.list {
+        try!(list_tests(options, test_args, &compilation));
+        return Ok(None)
     }
+    let mut results = TestResults { failures: Vec::new(), suites: Vec::new() };
+    let mut errors = try!(run_unit_tests(options, test_args, &compilation,
+                                         &mut results));
 
-    // If a specific test was requested or we're not running any tests at all,
-    // don't run any doc tests.
-    if let ops::CompileFilter::Only { .. } = options.compile_opts.filter {
-        match errors.len() {
-            0 => return Ok(None),
-            _ => return Ok(Some(CargoTestError::new(errors)))
-        }
+    // If we have an error and want to fail fast, or a specific test was
+    // requested or we're not running any tests at all, don't run any doc
+    // tests.
+    let fail_fast = errors.len() > 0 && !options.no_fail_fast;
+    let only = match options.compile_opts.filter {
+        ops::CompileFilter::Only { .. } => true,
+        ops::CompileFilter::Everything => false,
+    };
+    if !fail_fast && !only {
+        errors.extend(try!(run_doc_tests(options, test_args, &compilation,
+                                         &mut results)));
     }
 
-    errors.extend(try!(run_doc_tests(options, test_args, &compilation)));
+    try!(print_reruns(options, &results.failures));
+    if let Some(ref report) = options.report {
+        try!(report.write(&options.compile_opts.config.cwd(), &results.suites));
+    }
     if errors.len() == 0 {
         Ok(None)
     } else {
@@ -51,7 +151,9 @@ pub fn run_benches(manifest_path: &Path,
     let mut args = args.to_vec();
     args.push("--bench".to_string());
     let compilation = try!(compile_tests(manifest_path, options));
-    let errors = try!(run_unit_tests(options, &args, &compilation));
+    let mut results = TestResults { failures: Vec::new(), suites: Vec::new() };
+    let errors = try!(run_unit_tests(options, &args, &compilation,
+                                     &mut results));
     match errors.len() {
         0 => Ok(None),
         _ => Ok(Some(CargoTestError::new(errors))),
@@ -64,37 +166,107 @@ fn compile_tests<'a>(manifest_path: &Path,
     let mut compilation = try!(ops::compile(manifest_path,
                                             &options.compile_opts));
     compilation.tests.sort_by(|a, b| {
-        (a.0.package_id(), &a.1).cmp(&(b.0.package_id(), &b.1))
+        (a.0.package_id(), a.1.name()).cmp(&(b.0.package_id(), b.1.name()))
     });
     Ok(compilation)
 }
 
 /// Run the unit and integration tests of a project.
-fn run_unit_tests(options: &TestOptions,
-                  test_args: &[String],
-                  compilation: &Compilation)
-                  -> CargoResult<Vec<ProcessError>> {
-    let config = options.compile_opts.config;
+fn run_unit_tests<'a>(options: &TestOptions,
+                      test_args: &[String],
+                      compilation: &'a Compilation,
+                      results: &mut TestResults<'a>)
+                      -> CargoResult<Vec<ProcessError>> {
     let cwd = options.compile_opts.config.cwd();
+    let harness = harness_args(options, test_args);
 
-    let mut errors = Vec::new();
-
-    for &(ref pkg, _, ref exe) in &compilation.tests {
+    let mut runs = Vec::new();
+    for &(ref pkg, ref target, ref exe) in &compilation.tests {
         let to_display = match util::without_prefix(exe, &cwd) {
             Some(path) => path,
             None => &**exe,
         };
         let mut cmd = try!(compilation.target_process(exe, pkg));
         cmd.args(test_args);
-        try!(config.shell().concise(|shell| {
-            shell.status("Running", to_display.display().to_string())
-        }));
-        try!(config.shell().verbose(|shell| {
-            shell.status("Running", cmd.to_string())
-        }));
+        if target.harness() {
+            cmd.args(&harness);
+        }
+        runs.push(TestRun {
+            pkg: pkg,
+            target: Some(target),
+            name: to_display.display().to_string(),
+            cmd: cmd.into_process_builder(),
+        });
+    }
+    run_all(options, runs, results)
+}
 
-        if let Err(e) = ExecEngine::exec(&mut ProcessEngine, cmd) {
+fn run_doc_tests<'a>(options: &TestOptions,
+                     test_args: &[String],
+                     compilation: &'a Compilation,
+                     results: &mut TestResults<'a>)
+                     -> CargoResult<Vec<ProcessError>> {
+    let mut args = test_args.to_vec();
+    args.extend(harness_args(options, test_args));
+    let mut runs = Vec::new();
+    for (package, lib) in doc_tests(compilation) {
+        let p = try!(doc_test_process(compilation, package, lib, &args));
+        runs.push(TestRun {
+            pkg: package,
+            target: None,
+            name: lib.name().to_string(),
+            cmd: p.into_process_builder(),
+        });
+    }
+    run_all(options, runs, results)
+}
+
+/// Arguments which only the libtest harness understands, and so are left
+/// out for `harness = false` targets.
+///
+/// Besides `--exact`, this asks the harness for colored output when our own
+/// output is colored. The output of test binaries is piped through Cargo, so
+/// the harness can't see the terminal for itself. No color is asked for if
+/// `--color` was already passed on to the tests.
+fn harness_args(options: &TestOptions, test_args: &[String]) -> Vec<String> {
+    let mut args = Vec::new();
+    if options.exact {
+        args.push("--exact".to_string());
+    }
+    let config = options.compile_opts.config;
+    if !test_args.iter().any(|a| a == "--color" || a.starts_with("--color=")) &&
+       config.shell().out().colored() {
+        args.push("--color".to_string());
+        args.push("always".to_string());
+    }
+    args
+}
+
+/// Run each of `runs`, up to `--test-jobs` of them at once.
+fn run_all<'a>(options: &TestOptions,
+               runs: Vec<TestRun<'a>>,
+               results: &mut TestResults<'a>)
+               -> CargoResult<Vec<ProcessError>> {
+    let jobs = try!(test_jobs(options));
+    if jobs > 1 && runs.len() > 1 {
+        return run_parallel(options, jobs, runs, results)
+    }
+
+    let config = options.compile_opts.config;
+    let mut errors = Vec::new();
+    for run in runs.into_iter() {
+        try!(run.announce(config));
+        let mut failed = Vec::new();
+        let mut stdout = Vec::new();
+        let mut finished = Vec::new();
+        let start = time::precise_time_ns();
+        let res = exec_test(&run.cmd, &mut failed, &mut stdout, &mut finished);
+        if options.report.is_some() {
+            results.suites.push(run.suite(&stdout, start, &finished));
+        }
+        if let Err(e) = res {
             errors.push(e);
+            results.failures.push(run.failure(failed));
             if !options.no_fail_fast {
                 break
             }
@@ -103,76 +275,366 @@ fn run_unit_tests(options: &TestOptions,
     Ok(errors)
 }
 
-#[allow(deprecated)] // connect => join in 1.3
-fn run_doc_tests(options: &TestOptions,
-                 test_args: &[String],
-                 compilation: &Compilation)
-                 -> CargoResult<Vec<ProcessError>> {
-    let mut errors = Vec::new();
+/// Run `runs` on `jobs` threads.
+///
+/// Each binary's output is captured and printed in one go once it exits, so
+/// the output of binaries running at the same time is never mixed up. When
+/// failing fast, the first failure stops any more binaries from starting,
+/// though those already running are left to finish.
+fn run_parallel<'a>(options: &TestOptions,
+                    jobs: u32,
+                    runs: Vec<TestRun<'a>>,
+                    results: &mut TestResults<'a>)
+                    -> CargoResult<Vec<ProcessError>> {
     let config = options.compile_opts.config;
+    let cmds = runs.iter().map(|run| run.cmd.clone()).collect::<Vec<_>>();
+    let mut runs = runs.into_iter().map(Some).collect::<Vec<_>>();
+    let mut errors = Vec::new();
 
-    let libs = compilation.to_doc_test.iter().map(|package| {
-        (package, package.targets().iter().filter(|t| t.doctested())
-                         .map(|t| (t.src_path(), t.name(), t.crate_name())))
-    });
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = channel();
+    try!(crossbeam::scope(|scope| -> CargoResult<()> {
+        for _ in 0..cmp::min(jobs as usize, cmds.len()) {
+            let tx = tx.clone();
+            let next = &next;
+            let cmds = &cmds;
+            scope.spawn(move || {
+                loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let cmd = match cmds.get(i) {
+                        Some(cmd) => cmd,
+                        None => break,
+                    };
+                    let start = time::precise_time_ns();
+                    let res = cmd.build_command().output().map_err(|e| {
+                        process_error(&format!("Could not execute process {}",
+                                               cmd),
+                                      Some(e), None, None)
+                    });
+                    if tx.send((i, start, res)).is_err() { break }
+                }
+            });
+        }
+        drop(tx);
 
-    for (package, tests) in libs {
-        for (lib, name, crate_name) in tests {
-            try!(config.shell().status("Doc-tests", name));
-            let mut p = try!(compilation.rustdoc_process(package));
-            p.arg("--test").arg(lib)
-             .arg("--crate-name").arg(&crate_name);
-
-            for &rust_dep in &[&compilation.deps_output, &compilation.root_output] {
-                let mut arg = OsString::from("dependency=");
-                arg.push(rust_dep);
-                p.arg("-L").arg(arg);
+        for (i, start, res) in rx.iter() {
+            let run = runs[i].take().unwrap();
+            try!(run.announce(config));
+            let output = match res {
+                Ok(output) => output,
+                Err(e) => {
+                    if options.report.is_some() {
+                        results.suites.push(run.suite(&[], start, &[]));
+                    }
+                    errors.push(e);
+                    results.failures.push(run.failure(Vec::new()));
+                    if !options.no_fail_fast {
+                        next.store(cmds.len(), Ordering::SeqCst);
+                    }
+                    continue
+                }
+            };
+            try!(io::stdout().write_all(&output.stdout));
+            try!(io::stdout().flush());
+            try!(io::stderr().write_all(&output.stderr));
+            if options.report.is_some() {
+                results.suites.push(run.suite(&output.stdout, start, &[]));
             }
-            for native_dep in compilation.native_dirs.values() {
-                p.arg("-L").arg(native_dep);
+            if output.status.success() { continue }
+
+            let failed = String::from_utf8_lossy(&output.stdout).lines()
+                                .filter_map(failed_test).collect();
+            errors.push(process_error(&format!("Process didn't exit \
+                                                successfully: {}", run.cmd),
+                                      None, Some(&output.status), None));
+            results.failures.push(run.failure(failed));
+            if !options.no_fail_fast {
+                next.store(cmds.len(), Ordering::SeqCst);
             }
+        }
+        Ok(())
+    }));
+    Ok(errors)
+}
 
-            if test_args.len() > 0 {
-                p.arg("--test-args").arg(&test_args.connect(" "));
+/// The number of test binaries to run at once, from `--test-jobs` or the
+/// `test.jobs` configuration key. Binaries are run one at a time by default.
+fn test_jobs(options: &TestOptions) -> CargoResult<u32> {
+    if let Some(jobs) = options.test_jobs {
+        if jobs == 0 {
+            bail!("--test-jobs must be at least 1")
+        }
+        return Ok(jobs)
+    }
+    match try!(options.compile_opts.config.get_i64("test.jobs")) {
+        Some((n, p)) => {
+            if n <= 0 {
+                bail!("test.jobs must be positive, but found {} in {:?}", n, p)
+            } else if n >= u32::max_value() as i64 {
+                bail!("test.jobs is too large: found {} in {:?}", n, p)
+            } else {
+                Ok(n as u32)
             }
+        }
+        None => Ok(1),
+    }
+}
 
-            for cfg in compilation.cfgs.iter() {
-                p.arg("--cfg").arg(cfg);
+/// Print the name of every test in every test target, prefixed by the
+/// target it's in, by asking each test binary and rustdoc for a `--list`.
+///
+/// Targets with `harness = false` are skipped, as they don't know `--list`
+/// and would run their tests instead.
+fn list_tests(options: &TestOptions,
+              test_args: &[String],
+              compilation: &Compilation) -> CargoResult<()> {
+    let mut args = test_args.to_vec();
+    if options.exact {
+        args.push("--exact".to_string());
+    }
+    args.push("--list".to_string());
+
+    for &(ref pkg, ref target, ref exe) in &compilation.tests {
+        if !target.harness() { continue }
+        let mut cmd = try!(compilation.target_process(exe, pkg));
+        cmd.args(&args);
+        try!(print_listed(cmd, &target_description(target)));
+    }
+
+    if let ops::CompileFilter::Only { .. } = options.compile_opts.filter {
+        return Ok(())
+    }
+    for (package, lib) in doc_tests(compilation) {
+        let p = try!(doc_test_process(compilation, package, lib, &args));
+        try!(print_listed(p, &format!("doc {}", lib.name())));
+    }
+    Ok(())
+}
+
+fn print_listed(cmd: CommandPrototype, target: &str) -> CargoResult<()> {
+    let output = try!(ExecEngine::exec_with_output(&mut ProcessEngine, cmd));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for line in stdout.lines() {
+        let name = line.trim_right();
+        let name = if name.ends_with(": test") {
+            &name[..name.len() - ": test".len()]
+        } else if name.ends_with(": bench") {
+            &name[..name.len() - ": bench".len()]
+        } else {
+            continue
+        };
+        println!("{}: {}", target, name);
+    }
+    Ok(())
+}
+
+fn doc_tests<'a>(compilation: &'a Compilation)
+                 -> Vec<(&'a Package, &'a Target)> {
+    compilation.to_doc_test.iter().flat_map(|package| {
+        package.targets().iter().filter(|t| t.doctested())
+               .map(move |t| (package, t))
+    }).collect()
+}
+
+#[allow(deprecated)] // connect => join in 1.3
+fn doc_test_process(compilation: &Compilation, package: &Package, lib: &Target,
+                    test_args: &[String]) -> CargoResult<CommandPrototype> {
+    let mut p = try!(compilation.rustdoc_process(package));
+    p.arg("--test").arg(lib.src_path())
+     .arg("--crate-name").arg(&lib.crate_name());
+
+    for &rust_dep in &[&compilation.deps_output, &compilation.root_output] {
+        let mut arg = OsString::from("dependency=");
+        arg.push(rust_dep);
+        p.arg("-L").arg(arg);
+    }
+    for native_dep in compilation.native_dirs.values() {
+        p.arg("-L").arg(native_dep);
+    }
+
+    if test_args.len() > 0 {
+        p.arg("--test-args").arg(&test_args.connect(" "));
+    }
+
+    for cfg in compilation.cfgs.iter() {
+        p.arg("--cfg").arg(cfg);
+    }
+
+    for (_, libs) in compilation.libraries.iter() {
+        for &(ref target, ref lib) in libs.iter() {
+            // Note that we can *only* doctest rlib outputs here.  A
+            // staticlib output cannot be linked by the compiler (it just
+            // doesn't do that). A dylib output, however, can be linked by
+            // the compiler, but will always fail. Currently all dylibs are
+            // built as "static dylibs" where the standard library is
+            // statically linked into the dylib. The doc tests fail,
+            // however, for now as they try to link the standard library
+            // dynamically as well, causing problems. As a result we only
+            // pass `--extern` for rlib deps and skip out on all other
+            // artifacts.
+            if lib.extension() != Some(OsStr::new("rlib")) &&
+               !target.for_host() {
+                continue
             }
+            let mut arg = OsString::from(target.crate_name());
+            arg.push("=");
+            arg.push(lib);
+            p.arg("--extern").arg(&arg);
+        }
+    }
+    Ok(p)
+}
+
+/// Run a test binary, or rustdoc testing a library, and record the names of
+/// the tests which fail in `failed`.
+///
+/// Output is passed through to our own stdout as it arrives, and read for
+/// the `test <name> ... ok` lines the test harness prints as each test
+/// finishes. The name of each test and when its line arrived are recorded in
+/// `finished`. All of the output is also kept in `output` for the test
+/// report.
+fn exec_test(cmd: &ProcessBuilder, failed: &mut Vec<String>,
+             output: &mut Vec<u8>, finished: &mut Vec<(String, u64)>)
+             -> Result<(), ProcessError> {
+    let mut command = cmd.build_command();
+    command.stdout(Stdio::piped());
+    let mut child = try!(command.spawn().map_err(|e| {
+        process_error(&format!("Could not execute process {}", cmd),
+                      Some(e), None, None)
+    }));
 
-            for (_, libs) in compilation.libraries.iter() {
-                for &(ref target, ref lib) in libs.iter() {
-                    // Note that we can *only* doctest rlib outputs here.  A
-                    // staticlib output cannot be linked by the compiler (it just
-                    // doesn't do that). A dylib output, however, can be linked by
-                    // the compiler, but will always fail. Currently all dylibs are
-                    // built as "static dylibs" where the standard library is
-                    // statically linked into the dylib. The doc tests fail,
-                    // however, for now as they try to link the standard library
-                    // dynamically as well, causing problems. As a result we only
-                    // pass `--extern` for rlib deps and skip out on all other
-                    // artifacts.
-                    if lib.extension() != Some(OsStr::new("rlib")) &&
-                       !target.for_host() {
-                        continue
+    {
+        let mut child_stdout = child.stdout.take().unwrap();
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        let mut buf = [0; 4096];
+        let mut line = Vec::new();
+        loop {
+            let n = match child_stdout.read(&mut buf) {
+                Ok(0) | Err(..) => break,
+                Ok(n) => n,
+            };
+            let _ = stdout.write_all(&buf[..n]);
+            let _ = stdout.flush();
+            output.extend(buf[..n].iter().cloned());
+
+            for &b in buf[..n].iter() {
+                if b != b'\n' {
+                    line.push(b);
+                    continue
+                }
+                let text = String::from_utf8_lossy(&line);
+                let text = test_report::strip_ansi(&text);
+                if let Some((name, outcome)) = test_report::parse_result(&text) {
+                    finished.push((name.to_string(), time::precise_time_ns()));
+                    if outcome == Outcome::Failed {
+                        failed.push(name.to_string());
                     }
-                    let mut arg = OsString::from(target.crate_name());
-                    arg.push("=");
-                    arg.push(lib);
-                    p.arg("--extern").arg(&arg);
                 }
+                line.clear();
             }
+        }
+    }
 
-            try!(config.shell().verbose(|shell| {
-                shell.status("Running", p.to_string())
-            }));
-            if let Err(e) = ExecEngine::exec(&mut ProcessEngine, p) {
-                errors.push(e);
-                if !options.no_fail_fast {
-                    return Ok(errors);
-                }
-            }
+    let status = try!(child.wait().map_err(|e| {
+        process_error(&format!("Could not execute process {}", cmd),
+                      Some(e), None, None)
+    }));
+    if status.success() {
+        Ok(())
+    } else {
+        Err(process_error(&format!("Process didn't exit successfully: {}", cmd),
+                          None, Some(&status), None))
+    }
+}
+
+fn failed_test(line: &str) -> Option<String> {
+    match test_report::parse_result(&test_report::strip_ansi(line)) {
+        Some((name, Outcome::Failed)) => Some(name.to_string()),
+        _ => None,
+    }
+}
+
+/// Tell the user how to run each of the failed tests again on its own.
+fn print_reruns(options: &TestOptions, failures: &[Failure]) -> CargoResult<()> {
+    if failures.is_empty() { return Ok(()) }
+
+    let mut msg = "\nTo rerun the failed tests, run:".to_string();
+    for failure in failures.iter() {
+        let base = rerun_command(options, failure);
+        // There's no way to select only the doc tests, so their command runs
+        // every test binary as well.
+        let note = match failure.target {
+            Some(..) => "",
+            None => "  # doc tests can't be run on their own",
+        };
+        if failure.tests.is_empty() {
+            msg.push_str(&format!("\n    {}{}", base, note));
+        }
+        for test in failure.tests.iter() {
+            msg.push_str(&format!("\n    {} -- --exact {}{}", base,
+                                  shell_quote(test), note));
         }
     }
-    Ok(errors)
+    try!(options.compile_opts.config.shell().err().say(msg, BLACK));
+    Ok(())
+}
+
+fn rerun_command(options: &TestOptions, failure: &Failure) -> String {
+    let opts = &options.compile_opts;
+    let mut cmd = "cargo test".to_string();
+    if !opts.spec.is_empty() {
+        cmd.push_str(&format!(" -p {}", failure.pkg.name()));
+    }
+    if let Some(target) = failure.target {
+        cmd.push_str(&format!(" {}", target_flag(target)));
+    }
+    if opts.release {
+        cmd.push_str(" --release");
+    }
+    if let Some(triple) = opts.target {
+        cmd.push_str(&format!(" --target {}", triple));
+    }
+    if !opts.features.is_empty() {
+        let features = opts.features.iter().map(|s| &s[..])
+                                     .collect::<Vec<_>>().join(" ");
+        cmd.push_str(&format!(" --features {}", shell_quote(&features)));
+    }
+    if opts.no_default_features {
+        cmd.push_str(" --no-default-features");
+    }
+    cmd
+}
+
+/// The flag selecting `target` on the command line, such as `--test foo`.
+fn target_flag(target: &Target) -> String {
+    match *target.kind() {
+        TargetKind::Lib(..) => "--lib".to_string(),
+        TargetKind::Bin => format!("--bin {}", target.name()),
+        TargetKind::Test => format!("--test {}", target.name()),
+        TargetKind::Bench => format!("--bench {}", target.name()),
+        TargetKind::Example => format!("--example {}", target.name()),
+        TargetKind::CustomBuild => unreachable!("build scripts aren't tested"),
+    }
+}
+
+fn target_description(target: &Target) -> String {
+    match *target.kind() {
+        TargetKind::Lib(..) => format!("lib {}", target.name()),
+        TargetKind::Bin => format!("bin {}", target.name()),
+        TargetKind::Test => format!("test {}", target.name()),
+        TargetKind::Bench => format!("bench {}", target.name()),
+        TargetKind::Example => format!("example {}", target.name()),
+        TargetKind::CustomBuild => format!("build script {}", target.name()),
+    }
+}
+
+fn shell_quote(s: &str) -> String {
+    if !s.is_empty() && s.chars().all(|c| {
+        c.is_alphanumeric() || "_-:./=".contains(c)
+    }) {
+        s.to_string()
+    } else {
+        format!("'{}'", s.replace("'", "'\\''"))
+    }
 }
@@ -1,6 +1,7 @@
 pub use self::cargo_clean::{clean, CleanOptions};
+pub use self::cargo_config::{config_get, config_list, config_set};
 pub use self::cargo_compile::{compile, compile_pkg, resolve_dependencies, CompileOptions};
-pub use self::cargo_compile::{CompileFilter, CompileMode};
+pub use self::cargo_compile::{CompileFilter, CompileMode, FilterRule};
 pub use self::cargo_read_manifest::{read_manifest,read_package,read_packages};
 pub use self::cargo_rustc::{compile_targets, Compilation, Layout, Kind, Unit};
 pub use self::cargo_rustc::{Context, LayoutProxy};
@@ -8,7 +9,7 @@ pub use self::cargo_rustc::{BuildOutput, BuildConfig, TargetConfig};
 pub use self::cargo_rustc::{CommandType, CommandPrototype, ExecEngine, ProcessEngine};
 pub use self::cargo_run::run;
 pub use self::cargo_install::{install, install_list, uninstall};
-pub use self::cargo_new::{new, NewOptions, VersionControl};
+pub use self::cargo_new::{new, init, NewOptions, VersionControl};
 pub use self::cargo_doc::{doc, DocOptions};
 pub use self::cargo_generate_lockfile::{generate_lockfile};
 pub use self::cargo_generate_lockfile::{update_lockfile};
@@ -16,19 +17,25 @@ pub use self::cargo_generate_lockfile::UpdateOptions;
 pub use self::lockfile::{load_lockfile, load_pkg_lockfile};
 pub use self::lockfile::{write_lockfile, write_pkg_lockfile};
 pub use self::cargo_test::{run_tests, run_benches, TestOptions};
+pub use self::test_report::{TestReport, ReportFormat};
 pub use self::cargo_package::package;
 pub use self::registry::{publish, registry_configuration, RegistryConfig};
-pub use self::registry::{registry_login, search, http_proxy_exists, http_handle};
+pub use self::registry::{registry_login, registry_logout, search, info};
+pub use self::registry::SearchOptions;
+pub use self::registry::{http_proxy_exists, http_handle};
 pub use self::registry::{modify_owners, yank, OwnersOptions};
 pub use self::cargo_fetch::{fetch, get_resolved_packages};
+pub use self::cargo_git_gc::{git_gc, GitGcOptions};
 pub use self::cargo_pkgid::pkgid;
 pub use self::resolve::{resolve_pkg, resolve_with_previous};
 
 mod cargo_clean;
 mod cargo_compile;
+mod cargo_config;
 mod cargo_doc;
 mod cargo_fetch;
 mod cargo_generate_lockfile;
+mod cargo_git_gc;
 mod cargo_install;
 mod cargo_new;
 mod cargo_package;
@@ -40,3 +47,4 @@ mod cargo_test;
 mod lockfile;
 mod registry;
 mod resolve;
+mod test_report;
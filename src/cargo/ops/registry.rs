@@ -1,13 +1,15 @@
-use std::collections::HashMap;
+use std::cmp;
 use std::env;
 use std::fs;
 use std::io::prelude::*;
 use std::iter::repeat;
-use std::path::{Path, PathBuf};
+use std::path::Path;
+use std::process::Stdio;
 
 use curl::http;
 use git2;
-use registry::{Registry, NewCrate, NewCrateDependency};
+use registry::{Registry, Crate, NewCrate, NewCrateDependency};
+use rustc_serialize::json;
 use term::color::BLACK;
 
 use core::source::Source;
@@ -18,39 +20,78 @@ use ops;
 use sources::{RegistrySource};
 use util::config;
 use util::paths;
-use util::{CargoResult, human, ChainError, ToUrl};
-use util::config::{Config, ConfigValue, Location};
+use util::{self, network, CargoResult, human, ChainError, ToUrl};
+use util::config::Config;
 use util::important_paths::find_root_manifest_for_wd;
 
 pub struct RegistryConfig {
     pub index: Option<String>,
     pub token: Option<String>,
+    pub credential_process: Option<String>,
 }
 
 pub fn publish(manifest_path: &Path,
                config: &Config,
                token: Option<String>,
                index: Option<String>,
-               verify: bool) -> CargoResult<()> {
+               registry_name: Option<String>,
+               verify: bool,
+               dry_run: bool) -> CargoResult<()> {
     let pkg = try!(Package::for_path(&manifest_path, config));
 
-    let (mut registry, reg_id) = try!(registry(config, token, index));
-    try!(verify_dependencies(&pkg, &reg_id));
+    try!(verify_publish_allowed(&pkg, registry_name.as_ref().map(|s| &s[..])));
+
+    let reg_id = try!(registry_source_id(config, index.clone(),
+                                         registry_name.as_ref()
+                                                      .map(|s| &s[..])));
+    try!(verify_dependencies(&pkg, &reg_id, config));
 
     // Prepare a tarball, with a non-surpressable warning if metadata
     // is missing since this is being put online.
     let tarball = try!(ops::package(manifest_path, config, verify,
                                     false, true)).unwrap();
 
+    // A dry run never talks to the registry, so it needs neither a token nor
+    // the network.
+    if dry_run {
+        return transmit(config, &pkg, &tarball, None, &reg_id)
+    }
+
     // Upload said tarball to the specified destination
+    let (mut registry, _) = try!(registry(config, token, index,
+                                          registry_name));
     try!(config.shell().status("Uploading", pkg.package_id().to_string()));
-    try!(transmit(&pkg, &tarball, &mut registry));
+    try!(transmit(config, &pkg, &tarball, Some(&mut registry), &reg_id));
 
     Ok(())
 }
 
-fn verify_dependencies(pkg: &Package, registry_src: &SourceId)
-                       -> CargoResult<()> {
+/// Checks the `publish` key of the manifest against the registry being
+/// published to, where no name means the central registry, `crates-io`.
+#[allow(deprecated)] // connect => join in 1.3
+fn verify_publish_allowed(pkg: &Package, registry: Option<&str>)
+                          -> CargoResult<()> {
+    let allowed = match pkg.manifest().publish() {
+        Some(allowed) => allowed,
+        None => return Ok(()),
+    };
+    let registry = registry.unwrap_or("crates-io");
+    if allowed.is_empty() {
+        bail!("`{}` cannot be published.\n`publish` is set to `false` or an \
+               empty list in Cargo.toml and prevents publishing.", pkg.name())
+    } else if !allowed.iter().any(|r| r == registry) {
+        bail!("`{}` cannot be published.\nThe registry `{}` is not listed in \
+               the `publish` value in Cargo.toml, which only allows: `{}`",
+              pkg.name(), registry, allowed.connect("`, `"))
+    }
+    Ok(())
+}
+
+fn verify_dependencies(pkg: &Package, registry_src: &SourceId,
+                       config: &Config) -> CargoResult<()> {
+    // Crates published to a named registry may depend on crates from any other
+    // registry, but the central registry only accepts its own crates.
+    let central = try!(SourceId::for_central(config));
     for dep in pkg.dependencies().iter() {
         if dep.source_id().is_path() {
             if dep.specified_req().is_none() {
@@ -58,6 +99,8 @@ fn verify_dependencies(pkg: &Package, registry_src: &SourceId)
                        when publishing.\ndependency `{}` does not specify \
                        a version", dep.name())
             }
+        } else if dep.source_id().is_registry() && *registry_src != central {
+            continue
         } else if dep.source_id() != registry_src {
             bail!("all dependencies must come from the same source.\n\
                    dependency `{}` comes from {} instead",
@@ -67,9 +110,20 @@ fn verify_dependencies(pkg: &Package, registry_src: &SourceId)
     Ok(())
 }
 
-fn transmit(pkg: &Package, tarball: &Path, registry: &mut Registry)
-            -> CargoResult<()> {
+fn transmit(config: &Config,
+            pkg: &Package,
+            tarball: &Path,
+            registry: Option<&mut Registry>,
+            registry_id: &SourceId) -> CargoResult<()> {
     let deps = pkg.dependencies().iter().map(|dep| {
+        // Dependencies from a registry other than the one being published to
+        // are recorded with the index they come from.
+        let dep_registry = if dep.source_id().is_registry() &&
+                              dep.source_id() != registry_id {
+            Some(dep.source_id().url().to_string())
+        } else {
+            None
+        };
         NewCrateDependency {
             optional: dep.is_optional(),
             default_features: dep.uses_default_features(),
@@ -77,6 +131,7 @@ fn transmit(pkg: &Package, tarball: &Path, registry: &mut Registry)
             features: dep.features().to_vec(),
             version_req: dep.version_req().to_string(),
             target: dep.only_for_platform().map(|s| s.to_string()),
+            registry: dep_registry,
             kind: match dep.kind() {
                 Kind::Normal => "normal",
                 Kind::Build => "build",
@@ -101,7 +156,7 @@ fn transmit(pkg: &Package, tarball: &Path, registry: &mut Registry)
         }
         None => {}
     }
-    registry.publish(&NewCrate {
+    let krate = NewCrate {
         name: pkg.name().to_string(),
         vers: pkg.version().to_string(),
         deps: deps,
@@ -115,38 +170,190 @@ fn transmit(pkg: &Package, tarball: &Path, registry: &mut Registry)
         repository: repository.clone(),
         license: license.clone(),
         license_file: license_file.clone(),
-    }, tarball).map_err(|e| {
+    };
+
+    // Everything up to the upload itself has been checked at this point, so
+    // with no registry to upload to show what would be sent instead.
+    let registry = match registry {
+        Some(registry) => registry,
+        None => {
+            try!(config.shell().warn("warning: aborting upload due to dry run"));
+            try!(config.shell().say(json::as_pretty_json(&krate), BLACK));
+            try!(config.shell().say(format!("tarball: {}", tarball.display()),
+                                    BLACK));
+            return Ok(())
+        }
+    };
+
+    registry.publish(&krate, tarball).map_err(|e| {
         human(e.to_string())
     })
 }
 
-pub fn registry_configuration(config: &Config) -> CargoResult<RegistryConfig> {
-    let index = try!(config.get_string("registry.index")).map(|p| p.0);
-    let token = try!(config.get_string("registry.token")).map(|p| p.0);
-    Ok(RegistryConfig { index: index, token: token })
+/// Reads the index and token of a registry from the configuration.
+///
+/// With no name this is the default `[registry]` table, otherwise it is the
+/// `[registries.<name>]` table, which must specify an index. Tokens saved by
+/// `cargo login` live in the credentials file, which is merged in as part of
+/// the configuration.
+pub fn registry_configuration(config: &Config,
+                              registry: Option<&str>)
+                              -> CargoResult<RegistryConfig> {
+    let key = registry_key(registry);
+    let index = try!(config.get_string(&format!("{}.index", key)));
+    let token = try!(config.get_string(&format!("{}.token", key)));
+    if let Some(registry) = registry {
+        if index.is_none() {
+            bail!("no index found for registry: `{}`", registry)
+        }
+    }
+    let process = match try!(config.get_string(&format!("{}.credential-process",
+                                                        key))) {
+        Some(process) => Some(process),
+        None => try!(config.get_string("registry.credential-process")),
+    };
+    Ok(RegistryConfig {
+        index: index.map(|p| p.0),
+        token: token.map(|p| p.0),
+        credential_process: process.map(|p| p.0),
+    })
 }
 
-pub fn registry(config: &Config,
-                token: Option<String>,
-                index: Option<String>) -> CargoResult<(Registry, SourceId)> {
+/// The configuration table which describes a registry.
+fn registry_key(registry: Option<&str>) -> String {
+    match registry {
+        Some(registry) => format!("registries.{}", registry),
+        None => "registry".to_string(),
+    }
+}
+
+/// Finds the registry which `index` or the `registry` name refers to,
+/// defaulting to crates.io, without contacting it.
+fn registry_source_id(config: &Config,
+                      index: Option<String>,
+                      registry: Option<&str>) -> CargoResult<SourceId> {
+    if index.is_some() && registry.is_some() {
+        bail!("a registry index and `--registry` cannot both be specified")
+    }
+    let index = match index {
+        Some(index) => index,
+        None => {
+            let cfg = try!(registry_configuration(config, registry));
+            cfg.index.unwrap_or(RegistrySource::default_url())
+        }
+    };
+    let index = try!(index.to_url().map_err(human));
+    Ok(SourceId::for_registry(&index))
+}
+
+pub fn registry<'a>(config: &'a Config,
+                    token: Option<String>,
+                    index: Option<String>,
+                    registry: Option<String>)
+                    -> CargoResult<(Registry<'a>, SourceId)> {
+    let sid = try!(registry_source_id(config, index,
+                                      registry.as_ref().map(|s| &s[..])));
     // Parse all configuration options
     let RegistryConfig {
         token: token_config,
-        index: index_config,
-    } = try!(registry_configuration(config));
-    let token = token.or(token_config);
-    let index = index.or(index_config).unwrap_or(RegistrySource::default_url());
-    let index = try!(index.to_url().map_err(human));
-    let sid = SourceId::for_registry(&index);
+        credential_process,
+        ..
+    } = try!(registry_configuration(config, registry.as_ref().map(|s| &s[..])));
+    let token = match token.or(token_config) {
+        Some(token) => Some(token),
+        None => match credential_process {
+            Some(ref process) => {
+                let name = registry.as_ref().map(|s| &s[..]);
+                try!(run_credential_process(process, "get", name, None))
+            }
+            None => None,
+        },
+    };
+    let registry = try!(open_registry(config, &sid, token));
+    Ok((registry, sid))
+}
+
+/// Like `registry`, but for commands such as `search` which need no token,
+/// so none is looked up and no credential process is run.
+fn public_registry<'a>(config: &'a Config,
+                       index: Option<String>,
+                       registry: Option<String>)
+                       -> CargoResult<(Registry<'a>, SourceId)> {
+    let sid = try!(registry_source_id(config, index,
+                                      registry.as_ref().map(|s| &s[..])));
+    let registry = try!(open_registry(config, &sid, None));
+    Ok((registry, sid))
+}
+
+fn open_registry<'a>(config: &'a Config, sid: &SourceId,
+                     token: Option<String>) -> CargoResult<Registry<'a>> {
     let api_host = {
-        let mut src = RegistrySource::new(&sid, config);
+        let mut src = RegistrySource::new(sid, config);
         try!(src.update().chain_error(|| {
-            human(format!("failed to update registry {}", index))
+            human(format!("failed to update registry {}", sid.url()))
         }));
         (try!(src.config())).api
     };
     let handle = try!(http_handle(config));
-    Ok((Registry::new_handle(api_host, token, handle), sid))
+    let mut registry = Registry::new_handle(api_host, token, handle);
+    let retries = try!(network::retries(config));
+    registry.set_retry(move |e, attempt| {
+        network::should_retry(retries, attempt, e, |msg| {
+            try!(config.shell().warn(msg));
+            Ok(())
+        }).unwrap_or(false)
+    });
+    Ok(registry)
+}
+
+/// Runs an external credential process to `get`, `store` or `erase` the
+/// token for a registry.
+///
+/// The action is passed as the last argument and the registry's name in the
+/// `CARGO_REGISTRY_NAME` environment variable. A token being stored is
+/// written to the process's stdin, and a token being fetched is read from the
+/// first line of its stdout.
+fn run_credential_process(process: &str,
+                          action: &str,
+                          registry: Option<&str>,
+                          token: Option<&str>) -> CargoResult<Option<String>> {
+    let mut args = process.split_whitespace();
+    let program = match args.next() {
+        Some(program) => program,
+        None => bail!("the credential process for `{}` is empty",
+                      registry.unwrap_or("crates-io")),
+    };
+    let mut cmd = util::process(program);
+    for arg in args {
+        cmd.arg(arg);
+    }
+    cmd.arg(action).env("CARGO_REGISTRY_NAME", registry.unwrap_or("crates-io"));
+
+    let mut child = try!(cmd.build_command()
+                            .stdin(Stdio::piped())
+                            .stdout(Stdio::piped())
+                            .stderr(Stdio::inherit())
+                            .spawn()
+                            .chain_error(|| {
+        human(format!("failed to run credential process `{}`", process))
+    }));
+    if let Some(token) = token {
+        let mut stdin = child.stdin.take().unwrap();
+        try!(writeln!(stdin, "{}", token));
+    }
+    drop(child.stdin.take());
+    let output = try!(child.wait_with_output());
+    if !output.status.success() {
+        bail!("credential process `{}` failed to {} a token ({})",
+              process, action, output.status)
+    }
+
+    let stdout = try!(String::from_utf8(output.stdout).map_err(|_| {
+        human(format!("credential process `{}` did not print valid UTF-8",
+                      process))
+    }));
+    Ok(stdout.lines().next().map(|s| s.trim().to_string())
+             .and_then(|s| if s.is_empty() { None } else { Some(s) }))
 }
 
 /// Create a new HTTP handle with appropriate global configuration for cargo.
@@ -219,26 +426,62 @@ pub fn http_timeout(config: &Config) -> CargoResult<Option<i64>> {
     Ok(env::var("HTTP_TIMEOUT").ok().and_then(|s| s.parse().ok()))
 }
 
-pub fn registry_login(config: &Config, token: String) -> CargoResult<()> {
-    let RegistryConfig { index, token: _ } = try!(registry_configuration(config));
-    let mut map = HashMap::new();
-    let p = config.cwd().to_path_buf();
-    match index {
-        Some(index) => {
-            map.insert("index".to_string(), ConfigValue::String(index, p.clone()));
-        }
-        None => {}
+pub fn registry_login(config: &Config,
+                      token: String,
+                      registry: Option<String>) -> CargoResult<()> {
+    // Also makes sure that a named registry is actually configured before
+    // saving a token for it.
+    let RegistryConfig {
+        credential_process, ..
+    } = try!(registry_configuration(config, registry.as_ref().map(|s| &s[..])));
+
+    if let Some(process) = credential_process {
+        try!(run_credential_process(&process, "store",
+                                    registry.as_ref().map(|s| &s[..]),
+                                    Some(&token)));
+        return Ok(())
     }
-    map.insert("token".to_string(), ConfigValue::String(token, p));
 
-    config::set_config(config, Location::Global, "registry",
-                       ConfigValue::Table(map, PathBuf::from(".")))
+    let key = registry_key(registry.as_ref().map(|s| &s[..]));
+    try!(config::save_credentials(config, &key, Some(token)));
+    Ok(())
+}
+
+pub fn registry_logout(config: &Config,
+                       registry: Option<String>) -> CargoResult<()> {
+    let name = registry.as_ref().map(|s| &s[..]);
+    let RegistryConfig {
+        credential_process, ..
+    } = try!(registry_configuration(config, name));
+
+    let removed = match credential_process {
+        Some(process) => {
+            try!(run_credential_process(&process, "erase", name, None));
+            true
+        }
+        None => {
+            try!(config::save_credentials(config, &registry_key(name), None))
+        }
+    };
+
+    let name = name.unwrap_or("crates-io");
+    if removed {
+        try!(config.shell().status("Logout",
+                                   format!("token for `{}` has been removed \
+                                            from local storage", name)));
+    } else {
+        try!(config.shell().status("Logout",
+                                   format!("not currently logged in to `{}`",
+                                           name)));
+    }
+    Ok(())
 }
 
 pub struct OwnersOptions {
     pub krate: Option<String>,
     pub token: Option<String>,
     pub index: Option<String>,
+    pub registry: Option<String>,
     pub to_add: Option<Vec<String>>,
     pub to_remove: Option<Vec<String>>,
     pub list: bool,
@@ -255,7 +498,8 @@ pub fn modify_owners(config: &Config, opts: &OwnersOptions) -> CargoResult<()> {
     };
 
     let (mut registry, _) = try!(registry(config, opts.token.clone(),
-                                          opts.index.clone()));
+                                          opts.index.clone(),
+                                          opts.registry.clone()));
 
     match opts.to_add {
         Some(ref v) => {
@@ -304,6 +548,7 @@ pub fn yank(config: &Config,
             version: Option<String>,
             token: Option<String>,
             index: Option<String>,
+            registry_name: Option<String>,
             undo: bool) -> CargoResult<()> {
     let name = match krate {
         Some(name) => name,
@@ -318,7 +563,7 @@ pub fn yank(config: &Config,
         None => bail!("a version must be specified to yank")
     };
 
-    let (mut registry, _) = try!(registry(config, token, index));
+    let (mut registry, _) = try!(registry(config, token, index, registry_name));
 
     if undo {
         try!(config.shell().status("Unyank", format!("{}:{}", name, version)));
@@ -335,7 +580,20 @@ pub fn yank(config: &Config,
     Ok(())
 }
 
-pub fn search(query: &str, config: &Config, index: Option<String>) -> CargoResult<()> {
+pub struct SearchOptions {
+    pub index: Option<String>,
+    pub registry: Option<String>,
+    pub limit: u32,
+    pub sort: Option<String>,
+    pub json: bool,
+}
+
+/// The most results the registry is asked for in a single request.
+const SEARCH_PAGE_SIZE: u32 = 100;
+
+pub fn search(query: &str,
+              config: &Config,
+              opts: SearchOptions) -> CargoResult<()> {
     fn truncate_with_ellipsis(s: &str, max_length: usize) -> String {
         if s.len() < max_length {
             s.to_string()
@@ -344,10 +602,49 @@ pub fn search(query: &str, config: &Config, index: Option<String>) -> CargoResul
         }
     }
 
-    let (mut registry, _) = try!(registry(config, None, index));
-    let crates = try!(registry.search(query).map_err(|e| {
-        human(format!("failed to retrieve search results from the registry: {}", e))
-    }));
+    #[derive(RustcEncodable)]
+    struct SearchResults<'a> {
+        crates: &'a [Crate],
+        total: u32,
+    }
+
+    let sort = match opts.sort.as_ref().map(|s| &s[..]) {
+        None | Some("relevance") => None,
+        Some("downloads") => Some("downloads"),
+        Some("recent") => Some("recent-updates"),
+        Some(other) => {
+            bail!("unknown sort order `{}`, expected one of `relevance`, \
+                   `downloads` or `recent`", other)
+        }
+    };
+
+    let (mut registry, _) = try!(public_registry(config, opts.index,
+                                                 opts.registry));
+    let per_page = cmp::min(opts.limit, SEARCH_PAGE_SIZE);
+    let mut crates = Vec::new();
+    let mut total = 0;
+    let mut page = 1;
+    while (crates.len() as u32) < opts.limit {
+        let (results, count) = try!(registry.search(query, page, per_page,
+                                                    sort).map_err(|e| {
+            human(format!("failed to retrieve search results from the \
+                           registry: {}", e))
+        }));
+        total = count;
+        let done = results.len() < per_page as usize;
+        crates.extend(results.into_iter());
+        if done || crates.len() as u32 >= total {
+            break
+        }
+        page += 1;
+    }
+    crates.truncate(opts.limit as usize);
+
+    if opts.json {
+        let results = SearchResults { crates: &crates, total: total };
+        try!(config.shell().say(json::encode(&results).unwrap(), BLACK));
+        return Ok(())
+    }
 
     let list_items = crates.iter()
         .map(|krate| (
@@ -373,5 +670,98 @@ pub fn search(query: &str, config: &Config, index: Option<String>) -> CargoResul
         try!(config.shell().say(line, BLACK));
     }
 
+    // Let the user know when there were more matches than were shown.
+    let shown = crates.len() as u32;
+    if total > shown {
+        try!(config.shell().say(format!("... and {} crates more (use --limit \
+                                         N to see more)", total - shown),
+                                BLACK));
+    }
+
+    Ok(())
+}
+
+pub fn info(krate: &str,
+            config: &Config,
+            index: Option<String>,
+            registry_name: Option<String>,
+            reverse_deps: bool) -> CargoResult<()> {
+    let (mut registry, _) = try!(public_registry(config, index,
+                                                 registry_name));
+    let details = try!(registry.crate_info(krate).map_err(|e| {
+        human(format!("failed to retrieve crate `{}` from the registry: {}",
+                      krate, e))
+    }));
+    let info = &details.krate;
+    let license = details.versions.iter()
+                         .find(|v| v.num == info.max_version)
+                         .and_then(|v| v.license.clone());
+
+    let mut lines = vec![format!("{} v{}", info.name, info.max_version)];
+    if let Some(ref description) = info.description {
+        lines.push(description.trim().to_string());
+    }
+    let fields = [("license", license.as_ref()),
+                  ("homepage", info.homepage.as_ref()),
+                  ("documentation", info.documentation.as_ref()),
+                  ("repository", info.repository.as_ref())];
+    for &(name, value) in fields.iter() {
+        if let Some(value) = value {
+            lines.push(format!("{}: {}", name, value));
+        }
+    }
+    if let Some(downloads) = info.downloads {
+        lines.push(format!("downloads: {}", downloads));
+    }
+
+    lines.push("versions:".to_string());
+    let width = details.versions.iter().map(|v| v.num.len()).max().unwrap_or(0);
+    for version in details.versions.iter() {
+        let num = version.num.clone() +
+                  &repeat(' ').take(width - version.num.len())
+                              .collect::<String>();
+        // Only the date part of the timestamp is interesting here.
+        let mut line = format!("    {}", num);
+        if let Some(date) = version.created_at.as_ref()
+                                   .and_then(|d| d.split('T').next()) {
+            line.push_str(&format!("  {}", date));
+        }
+        if let Some(downloads) = version.downloads {
+            line.push_str(&format!("  {} downloads", downloads));
+        }
+        if version.yanked {
+            line.push_str(" (yanked)");
+        }
+        lines.push(line.trim_right().to_string());
+    }
+
+    if reverse_deps {
+        let (rdeps, total) = try!(registry.reverse_dependencies(krate)
+                                          .map_err(|e| {
+            human(format!("failed to retrieve reverse dependencies of `{}`: \
+                           {}", krate, e))
+        }));
+        if rdeps.len() < total as usize {
+            lines.push(format!("reverse dependencies (showing {} of {}):",
+                               rdeps.len(), total));
+        } else {
+            lines.push(format!("reverse dependencies ({}):", total));
+        }
+        for dep in rdeps.iter() {
+            let mut line = format!("    {} v{} ({})", dep.name, dep.version,
+                                   dep.req);
+            if dep.kind != "normal" {
+                line.push_str(&format!(" [{}]", dep.kind));
+            }
+            if dep.optional {
+                line.push_str(" optional");
+            }
+            lines.push(line);
+        }
+    }
+
+    for line in lines.into_iter() {
+        try!(config.shell().say(line, BLACK));
+    }
     Ok(())
 }
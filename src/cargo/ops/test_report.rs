@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustc_serialize::{Decodable, Decoder};
+use rustc_serialize::json;
+
+use util::{CargoResult, ChainError, human, paths};
+
+/// Where `cargo test --report` writes its results, and in which format.
+#[derive(Clone, Debug)]
+pub struct TestReport {
+    pub format: ReportFormat,
+    pub path: PathBuf,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReportFormat { Junit, Json }
+
+impl Decodable for TestReport {
+    fn decode<D: Decoder>(d: &mut D) -> Result<TestReport, D::Error> {
+        let s = try!(d.read_str());
+        let mut parts = s.splitn(2, '=');
+        let format = match parts.next() {
+            Some("junit") => ReportFormat::Junit,
+            Some("json") => ReportFormat::Json,
+            _ => {
+                let err = format!("could not decode '{}' as a report, \
+                                   expected `junit=PATH` or `json=PATH`", s);
+                return Err(d.error(&err))
+            }
+        };
+        match parts.next() {
+            Some(path) if !path.is_empty() => {
+                Ok(TestReport { format: format, path: PathBuf::from(path) })
+            }
+            _ => Err(d.error(&format!("no path given for the report in '{}'", s))),
+        }
+    }
+}
+
+/// The results of running one test binary, or the doc tests of a library.
+pub struct Suite {
+    pub package: String,
+    /// Which target the tests came from, such as `test foo` or `doc foo`
+    pub target: String,
+    /// How long the whole binary took to run, in seconds
+    pub time: f64,
+    pub cases: Vec<TestCase>,
+}
+
+pub struct TestCase {
+    pub name: String,
+    pub outcome: Outcome,
+    /// Output captured by the test harness, only kept for failed tests
+    pub stdout: Option<String>,
+    /// How long the test took in seconds, if known
+    pub time: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome { Passed, Failed, Ignored }
+
+impl Outcome {
+    fn as_str(&self) -> &'static str {
+        match *self {
+            Outcome::Passed => "passed",
+            Outcome::Failed => "failed",
+            Outcome::Ignored => "ignored",
+        }
+    }
+}
+
+/// Read the results of each test out of what a libtest harness printed.
+///
+/// The harness doesn't say how long each test took, so cases are left
+/// untimed here. Anything which isn't libtest output, such as that of a
+/// `harness = false` test, simply yields no cases.
+pub fn parse_output(stdout: &str) -> Vec<TestCase> {
+    let stdout = strip_ansi(stdout);
+    let mut cases = Vec::new();
+    let mut outputs = HashMap::new();
+    let mut current = None;
+
+    for line in stdout.lines() {
+        if let Some((name, outcome)) = parse_result(line) {
+            cases.push(TestCase {
+                name: name.to_string(),
+                outcome: outcome,
+                stdout: None,
+                time: None,
+            });
+            current = None;
+            continue
+        }
+        if line.starts_with("---- ") && line.ends_with(" stdout ----") {
+            let name = &line["---- ".len()..line.len() - " stdout ----".len()];
+            current = Some(name.to_string());
+            continue
+        }
+        if line == "failures:" || line.starts_with("test result:") {
+            current = None;
+            continue
+        }
+        if let Some(ref name) = current {
+            let out = outputs.entry(name.clone()).or_insert(String::new());
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+
+    for case in cases.iter_mut() {
+        if case.outcome != Outcome::Failed { continue }
+        case.stdout = outputs.remove(&case.name).map(|s| s.trim().to_string());
+    }
+    cases
+}
+
+/// Read the name and outcome of a test out of the `test <name> ... ok` line
+/// the harness prints when it finishes. Colors must already be stripped.
+pub fn parse_result(line: &str) -> Option<(&str, Outcome)> {
+    let line = line.trim_right();
+    if !line.starts_with("test ") { return None }
+    let i = match line.rfind(" ... ") {
+        Some(i) => i,
+        None => return None,
+    };
+    let outcome = match &line[i + " ... ".len()..] {
+        "ok" => Outcome::Passed,
+        "FAILED" => Outcome::Failed,
+        "ignored" => Outcome::Ignored,
+        s if s.starts_with("bench:") => Outcome::Passed,
+        _ => return None,
+    };
+    Some((&line["test ".len()..i], outcome))
+}
+
+/// Remove the escape sequences the harness colors its output with when run
+/// with `--color always`.
+pub fn strip_ansi(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            ret.push(c);
+            continue
+        }
+        if chars.clone().next() == Some('[') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                if c >= '@' && c <= '~' { break }
+            }
+        }
+    }
+    ret
+}
+
+impl TestReport {
+    /// Write the results of every suite to the report, relative to `cwd`.
+    pub fn write(&self, cwd: &Path, suites: &[Suite]) -> CargoResult<()> {
+        let path = cwd.join(&self.path);
+        let contents = match self.format {
+            ReportFormat::Junit => junit(suites),
+            ReportFormat::Json => try!(to_json(suites)),
+        };
+        if let Some(parent) = path.parent() {
+            try!(fs::create_dir_all(parent));
+        }
+        paths::write(&path, contents.as_bytes()).chain_error(|| {
+            human(format!("failed to write the test report to `{}`",
+                          path.display()))
+        })
+    }
+}
+
+fn junit(suites: &[Suite]) -> String {
+    let mut out = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n"
+                      .to_string();
+    for suite in suites.iter() {
+        let count = |o| suite.cases.iter().filter(|c| c.outcome == o).count();
+        out.push_str(&format!("  <testsuite name=\"{}\" package=\"{}\" \
+                               tests=\"{}\" failures=\"{}\" skipped=\"{}\" \
+                               time=\"{:.3}\">\n",
+                              escape(&suite.target), escape(&suite.package),
+                              suite.cases.len(), count(Outcome::Failed),
+                              count(Outcome::Ignored), suite.time));
+        let classname = format!("{}::{}", suite.package, suite.target);
+        for case in suite.cases.iter() {
+            out.push_str(&format!("    <testcase name=\"{}\" classname=\"{}\"",
+                                  escape(&case.name), escape(&classname)));
+            if let Some(time) = case.time {
+                out.push_str(&format!(" time=\"{:.3}\"", time));
+            }
+            match case.outcome {
+                Outcome::Passed => out.push_str("/>\n"),
+                Outcome::Ignored => out.push_str(">\n      <skipped/>\n    </testcase>\n"),
+                Outcome::Failed => {
+                    out.push_str(">\n      <failure message=\"test failed\">");
+                    if let Some(ref stdout) = case.stdout {
+                        out.push_str(&cdata(stdout));
+                    }
+                    out.push_str("</failure>\n    </testcase>\n");
+                }
+            }
+        }
+        out.push_str("  </testsuite>\n");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+fn escape(s: &str) -> String {
+    s.replace("&", "&amp;").replace("<", "&lt;").replace(">", "&gt;")
+     .replace("\"", "&quot;")
+}
+
+fn cdata(s: &str) -> String {
+    // `]]>` can't appear inside a CDATA section, so split it across two
+    format!("<![CDATA[{}]]>", s.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+#[derive(RustcEncodable)]
+struct EncodableSuite<'a> {
+    package: &'a str,
+    target: &'a str,
+    time: f64,
+    tests: Vec<EncodableTestCase<'a>>,
+}
+
+#[derive(RustcEncodable)]
+struct EncodableTestCase<'a> {
+    name: &'a str,
+    outcome: &'static str,
+    stdout: Option<&'a str>,
+    time: Option<f64>,
+}
+
+fn to_json(suites: &[Suite]) -> CargoResult<String> {
+    let suites = suites.iter().map(|suite| {
+        EncodableSuite {
+            package: &suite.package,
+            target: &suite.target,
+            time: suite.time,
+            tests: suite.cases.iter().map(|case| {
+                EncodableTestCase {
+                    name: &case.name,
+                    outcome: case.outcome.as_str(),
+                    stdout: case.stdout.as_ref().map(|s| &s[..]),
+                    time: case.time,
+                }
+            }).collect(),
+        }
+    }).collect::<Vec<_>>();
+    let mut json = try!(json::encode(&suites).map_err(|e| {
+        human(format!("failed to encode the test report: {}", e))
+    }));
+    json.push('\n');
+    Ok(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{junit, parse_output, parse_result, strip_ansi, Outcome, Suite};
+
+    #[test]
+    fn parses_libtest_output() {
+        let cases = parse_output("
+running 3 tests
+test a ... ok
+test b ... ignored
+test c ... FAILED
+
+failures:
+
+---- c stdout ----
+\tthread 'c' panicked at 'boom', src/lib.rs:3
+
+
+failures:
+    c
+
+test result: FAILED. 1 passed; 1 failed; 1 ignored; 0 measured
+");
+        let outcomes = cases.iter().map(|c| (&c.name[..], c.outcome))
+                            .collect::<Vec<_>>();
+        assert_eq!(outcomes, [("a", Outcome::Passed), ("b", Outcome::Ignored),
+                              ("c", Outcome::Failed)]);
+        assert_eq!(cases[2].stdout, Some("thread 'c' panicked at 'boom', \
+                                          src/lib.rs:3".to_string()));
+        assert_eq!(cases[0].stdout, None);
+    }
+
+    #[test]
+    fn parses_colored_output() {
+        let cases = parse_output("
+running 2 tests
+test a ... \x1b[32mok\x1b[0m
+test b ... \x1b[31mFAILED\x1b[0m
+");
+        let outcomes = cases.iter().map(|c| (&c.name[..], c.outcome))
+                            .collect::<Vec<_>>();
+        assert_eq!(outcomes, [("a", Outcome::Passed), ("b", Outcome::Failed)]);
+        assert_eq!(strip_ansi("\x1b[1;31mred\x1b[0m"), "red");
+    }
+
+    #[test]
+    fn parses_result_lines() {
+        assert_eq!(parse_result("test a::b ... ok\r"),
+                   Some(("a::b", Outcome::Passed)));
+        assert_eq!(parse_result("test c ... bench:  10 ns/iter (+/- 1)"),
+                   Some(("c", Outcome::Passed)));
+        assert_eq!(parse_result("test result: ok. 1 passed"), None);
+    }
+
+    #[test]
+    fn junit_includes_case_times() {
+        let mut cases = parse_output("test a ... ok\ntest b ... ok\n");
+        cases[0].time = Some(0.25);
+        let xml = junit(&[Suite {
+            package: "foo".to_string(),
+            target: "lib foo".to_string(),
+            time: 0.5,
+            cases: cases,
+        }]);
+        assert!(xml.contains("<testcase name=\"a\" classname=\"foo::lib foo\" \
+                              time=\"0.250\"/>"), "{}", xml);
+        assert!(xml.contains("<testcase name=\"b\" \
+                              classname=\"foo::lib foo\"/>"), "{}", xml);
+    }
+}
@@ -1,7 +1,7 @@
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher, SipHasher};
 use std::mem;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use url::{self, Url};
 
@@ -68,6 +68,40 @@ impl<'cfg> GitSource<'cfg> {
 
     pub fn url(&self) -> &Url { self.remote.url() }
 
+    /// Fetch the repository and check out its files without looking for any
+    /// packages in them, returning the directory they were checked out to.
+    pub fn checkout(&mut self) -> CargoResult<&Path> {
+        try!(self.fetch_checkout());
+        Ok(&self.checkout_path)
+    }
+
+    fn fetch_checkout(&mut self) -> CargoResult<GitRevision> {
+        let actual_rev = self.remote.rev_for(&self.db_path, &self.reference);
+        // A full commit hash can never point anywhere else, so once it's in
+        // the database there's nothing new to fetch for it.
+        let should_update = actual_rev.is_err() ||
+                            (self.source_id.precise().is_none() &&
+                             !is_pinned(&self.reference));
+
+        let (repo, actual_rev) = if should_update {
+            try!(self.config.shell().status("Updating",
+                format!("git repository `{}`", self.remote.url())));
+
+            trace!("updating git source `{:?}`", self.remote);
+            let repo = try!(self.remote.checkout(&self.db_path, &self.reference,
+                                                 self.config));
+            let rev = try!(repo.rev_for(&self.reference));
+            (repo, rev)
+        } else {
+            (try!(self.remote.db_at(&self.db_path)), actual_rev.unwrap())
+        };
+
+        try!(repo.copy_to(actual_rev.clone(), &self.checkout_path,
+                          self.config));
+        try!(repo.mark_used());
+        Ok(actual_rev)
+    }
+
     pub fn read_packages(&mut self) -> CargoResult<Vec<Package>> {
         if self.path_source.is_none() {
             try!(self.update());
@@ -96,6 +130,17 @@ fn ident(url: &Url) -> String {
     format!("{}-{}", ident, to_hex(hasher.finish()))
 }
 
+/// Whether `reference` names a full commit hash rather than something which
+/// may move, like a branch or an abbreviated revision.
+fn is_pinned(reference: &GitReference) -> bool {
+    match *reference {
+        GitReference::Rev(ref s) => {
+            s.len() == 40 && s.chars().all(|c| c.is_digit(16))
+        }
+        _ => false,
+    }
+}
+
 // Some hacks and heuristics for making equivalent URLs hash the same
 pub fn canonicalize_url(url: &Url) -> Url {
     let mut url = url.clone();
@@ -169,23 +214,7 @@ impl<'cfg> Registry for GitSource<'cfg> {
 
 impl<'cfg> Source for GitSource<'cfg> {
     fn update(&mut self) -> CargoResult<()> {
-        let actual_rev = self.remote.rev_for(&self.db_path, &self.reference);
-        let should_update = actual_rev.is_err() ||
-                            self.source_id.precise().is_none();
-
-        let (repo, actual_rev) = if should_update {
-            try!(self.config.shell().status("Updating",
-                format!("git repository `{}`", self.remote.url())));
-
-            trace!("updating git source `{:?}`", self.remote);
-            let repo = try!(self.remote.checkout(&self.db_path));
-            let rev = try!(repo.rev_for(&self.reference));
-            (repo, rev)
-        } else {
-            (try!(self.remote.db_at(&self.db_path)), actual_rev.unwrap())
-        };
-
-        try!(repo.copy_to(actual_rev.clone(), &self.checkout_path));
+        let actual_rev = try!(self.fetch_checkout());
 
         let source_id = self.source_id.with_precise(Some(actual_rev.to_string()));
         let path_source = PathSource::new(&self.checkout_path, &source_id,
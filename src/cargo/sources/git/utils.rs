@@ -2,12 +2,15 @@ use std::fmt;
 use std::path::{Path, PathBuf};
 use std::fs::{self, File};
 
+use filetime::{self, FileTime};
 use rustc_serialize::{Encodable, Encoder};
+use time;
 use url::Url;
 use git2::{self, ObjectType};
 
 use core::GitReference;
-use util::{CargoResult, ChainError, human, ToUrl, internal};
+use util::{CargoResult, ChainError, Config, human, ToUrl, internal};
+use util::network;
 
 #[derive(PartialEq, Clone, Debug)]
 pub struct GitRevision(git2::Oid);
@@ -108,21 +111,42 @@ impl GitRemote {
         db.rev_for(reference)
     }
 
-    pub fn checkout(&self, into: &Path) -> CargoResult<GitDatabase> {
+    /// Fetch `reference` from this remote into the database at `into`,
+    /// creating the database if it doesn't exist yet.
+    ///
+    /// Only the refs needed to resolve `reference` are fetched, and tags are
+    /// not followed automatically. The full history of those refs is fetched
+    /// though, as libgit2 has no support for shallow fetches.
+    pub fn checkout(&self, into: &Path, reference: &GitReference,
+                    config: &Config) -> CargoResult<GitDatabase> {
+        let refspecs = refspecs_for(reference);
         let repo = match git2::Repository::open(into) {
             Ok(repo) => {
-                try!(self.fetch_into(&repo).chain_error(|| {
+                try!(self.fetch_into(&repo, &refspecs, config).chain_error(|| {
                     human(format!("failed to fetch into {}", into.display()))
                 }));
                 repo
             }
             Err(..) => {
-                try!(self.clone_into(into).chain_error(|| {
+                try!(self.clone_into(into, &refspecs, config).chain_error(|| {
                     human(format!("failed to clone into: {}", into.display()))
                 }))
             }
         };
 
+        // A revision which isn't on the default branch could be on any other
+        // one, or only reachable from a tag, so only then are all of the
+        // branches and tags fetched.
+        if let GitReference::Rev(ref rev) = *reference {
+            if repo.revparse_single(rev).is_err() {
+                let refspecs = ["refs/heads/*:refs/heads/*".to_string(),
+                                "refs/tags/*:refs/tags/*".to_string()];
+                try!(self.fetch_into(&repo, &refspecs, config).chain_error(|| {
+                    human(format!("failed to fetch into {}", into.display()))
+                }));
+            }
+        }
+
         Ok(GitDatabase {
             remote: self.clone(),
             path: into.to_path_buf(),
@@ -139,45 +163,64 @@ impl GitRemote {
         })
     }
 
-    fn fetch_into(&self, dst: &git2::Repository) -> CargoResult<()> {
+    fn fetch_into(&self, dst: &git2::Repository, refspecs: &[String],
+                  config: &Config) -> CargoResult<()> {
         // Create a local anonymous remote in the repository to fetch the url
         let url = self.url.to_string();
-        let refspec = "refs/heads/*:refs/heads/*";
-        fetch(dst, &url, refspec)
+        fetch(dst, &url, refspecs, config)
     }
 
-    fn clone_into(&self, dst: &Path) -> CargoResult<git2::Repository> {
+    fn clone_into(&self, dst: &Path, refspecs: &[String],
+                  config: &Config) -> CargoResult<git2::Repository> {
         let url = self.url.to_string();
         if fs::metadata(&dst).is_ok() {
             try!(fs::remove_dir_all(dst));
         }
         try!(fs::create_dir_all(dst));
         let repo = try!(git2::Repository::init_bare(dst));
-        try!(fetch(&repo, &url, "refs/heads/*:refs/heads/*"));
+        try!(fetch(&repo, &url, refspecs, config));
         Ok(repo)
     }
 }
 
+/// The refspecs which need to be fetched from a remote to resolve `reference`.
+///
+/// Branches and tags are fetched on their own. Revisions are usually on the
+/// default branch, so that's tried first for them.
+fn refspecs_for(reference: &GitReference) -> Vec<String> {
+    match *reference {
+        GitReference::Branch(ref b) => {
+            vec![format!("refs/heads/{0}:refs/heads/{0}", b)]
+        }
+        GitReference::Tag(ref t) => {
+            vec![format!("refs/tags/{0}:refs/tags/{0}", t)]
+        }
+        GitReference::Rev(..) => {
+            vec!["+HEAD:refs/remotes/origin/HEAD".to_string()]
+        }
+    }
+}
+
 impl GitDatabase {
     fn path<'a>(&'a self) -> &'a Path {
         &self.path
     }
 
-    pub fn copy_to(&self, rev: GitRevision, dest: &Path)
+    pub fn copy_to(&self, rev: GitRevision, dest: &Path, config: &Config)
                    -> CargoResult<GitCheckout> {
         let checkout = match git2::Repository::open(dest) {
             Ok(repo) => {
                 let checkout = GitCheckout::new(dest, self, rev, repo);
                 if !checkout.is_fresh() {
-                    try!(checkout.fetch());
+                    try!(checkout.fetch(config));
                     try!(checkout.reset());
                     assert!(checkout.is_fresh());
                 }
                 checkout
             }
-            Err(..) => try!(GitCheckout::clone_into(dest, self, rev)),
+            Err(..) => try!(GitCheckout::clone_into(dest, self, rev, config)),
         };
-        try!(checkout.update_submodules().chain_error(|| {
+        try!(checkout.update_submodules(config).chain_error(|| {
             internal("failed to update submodules")
         }));
         Ok(checkout)
@@ -218,6 +261,19 @@ impl GitDatabase {
         try!(self.repo.revparse_single(reference));
         Ok(())
     }
+
+    /// Record that this database was just used, so `cargo git-gc` keeps it
+    /// around.
+    pub fn mark_used(&self) -> CargoResult<()> {
+        let now = time::now_utc().to_timespec();
+        let now = FileTime::from_seconds_since_1970(now.sec as u64,
+                                                    now.nsec as u32);
+        try!(filetime::set_file_times(&self.path, now, now).chain_error(|| {
+            internal(format!("failed to update the mtime of {}",
+                             self.path.display()))
+        }));
+        Ok(())
+    }
 }
 
 impl<'a> GitCheckout<'a> {
@@ -234,15 +290,21 @@ impl<'a> GitCheckout<'a> {
     }
 
     fn clone_into(into: &Path, database: &'a GitDatabase,
-                  revision: GitRevision)
+                  revision: GitRevision, config: &Config)
                   -> CargoResult<GitCheckout<'a>>
     {
         let repo = try!(GitCheckout::clone_repo(database.path(), into));
         let checkout = GitCheckout::new(into, database, revision, repo);
+        try!(checkout.fetch(config));
         try!(checkout.reset());
         Ok(checkout)
     }
 
+    /// Create an empty repository at `into` to be filled in from the database
+    /// at `source`.
+    ///
+    /// A plain clone would only bring along the database's branches, but the
+    /// database may hold nothing but a tag, so `fetch` takes care of the rest.
     fn clone_repo(source: &Path, into: &Path) -> CargoResult<git2::Repository> {
         let dirname = into.parent().unwrap();
 
@@ -256,9 +318,7 @@ impl<'a> GitCheckout<'a> {
             }));
         }
 
-        let url = try!(source.to_url().map_err(human));
-        let url = url.to_string();
-        let repo = try!(git2::Repository::clone(&url, into).chain_error(|| {
+        let repo = try!(git2::Repository::init(into).chain_error(|| {
             internal(format!("failed to clone {} into {}", source.display(),
                              into.display()))
         }));
@@ -275,12 +335,16 @@ impl<'a> GitCheckout<'a> {
         }
     }
 
-    fn fetch(&self) -> CargoResult<()> {
+    fn fetch(&self, config: &Config) -> CargoResult<()> {
         info!("fetch {}", self.repo.path().display());
         let url = try!(self.database.path.to_url().map_err(human));
         let url = url.to_string();
-        let refspec = "refs/heads/*:refs/heads/*";
-        try!(fetch(&self.repo, &url, refspec));
+        // The database only holds the refs that were asked for, so take all
+        // of them; it's on local disk either way.
+        let refspecs = ["refs/heads/*:refs/heads/*".to_string(),
+                        "refs/tags/*:refs/tags/*".to_string(),
+                        "refs/remotes/origin/*:refs/remotes/origin/*".to_string()];
+        try!(fetch(&self.repo, &url, &refspecs, config));
         Ok(())
     }
 
@@ -302,10 +366,11 @@ impl<'a> GitCheckout<'a> {
         Ok(())
     }
 
-    fn update_submodules(&self) -> CargoResult<()> {
-        return update_submodules(&self.repo);
+    fn update_submodules(&self, config: &Config) -> CargoResult<()> {
+        return update_submodules(&self.repo, config);
 
-        fn update_submodules(repo: &git2::Repository) -> CargoResult<()> {
+        fn update_submodules(repo: &git2::Repository,
+                             config: &Config) -> CargoResult<()> {
             info!("update submodules for: {:?}", repo.workdir().unwrap());
 
             for mut child in try!(repo.submodules()).into_iter() {
@@ -343,15 +408,15 @@ impl<'a> GitCheckout<'a> {
                 };
 
                 // Fetch data from origin and reset to the head commit
-                let refspec = "refs/heads/*:refs/heads/*";
-                try!(fetch(&repo, url, refspec).chain_error(|| {
+                let refspecs = ["refs/heads/*:refs/heads/*".to_string()];
+                try!(fetch(&repo, url, &refspecs, config).chain_error(|| {
                     internal(format!("failed to fetch submodule `{}` from {}",
                                      child.name().unwrap_or(""), url))
                 }));
 
                 let obj = try!(repo.find_object(head, None));
                 try!(repo.reset(&obj, git2::ResetType::Hard, None));
-                try!(update_submodules(&repo));
+                try!(update_submodules(&repo, config));
             }
             Ok(())
         }
@@ -420,18 +485,26 @@ fn with_authentication<T, F>(url: &str, cfg: &git2::Config, mut f: F)
     }
 }
 
+/// Fetch `refspecs` from `url` into `repo`.
+///
+/// Tags are only fetched when a refspec names them. libgit2 can't do shallow
+/// fetches, but fetching into an existing repository only transfers the
+/// objects it's missing.
 pub fn fetch(repo: &git2::Repository, url: &str,
-             refspec: &str) -> CargoResult<()> {
+             refspecs: &[String], config: &Config) -> CargoResult<()> {
     // Create a local anonymous remote in the repository to fetch the url
 
-    with_authentication(url, &try!(repo.config()), |f| {
-        let mut cb = git2::RemoteCallbacks::new();
-        cb.credentials(f);
-        let mut remote = try!(repo.remote_anonymous(&url));
-        let mut opts = git2::FetchOptions::new();
-        opts.remote_callbacks(cb)
-            .download_tags(git2::AutotagOption::All);
-        try!(remote.fetch(&[refspec], Some(&mut opts), None));
-        Ok(())
+    network::with_retry(config, || {
+        with_authentication(url, &try!(repo.config()), |f| {
+            let mut cb = git2::RemoteCallbacks::new();
+            cb.credentials(f);
+            let mut remote = try!(repo.remote_anonymous(&url));
+            let mut opts = git2::FetchOptions::new();
+            opts.remote_callbacks(cb)
+                .download_tags(git2::AutotagOption::None);
+            let refspecs = refspecs.iter().map(|s| &s[..]).collect::<Vec<_>>();
+            try!(remote.fetch(&refspecs, Some(&mut opts), None));
+            Ok(())
+        })
     })
 }
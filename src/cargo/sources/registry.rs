@@ -158,11 +158,15 @@
 //!         ...
 //! ```
 
+use std::cmp;
 use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::prelude::*;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Sender};
 
+use crossbeam;
 use curl::http;
 use flate2::read::GzDecoder;
 use git2;
@@ -175,18 +179,20 @@ use core::{Source, SourceId, PackageId, Package, Summary, Registry};
 use core::dependency::{Dependency, DependencyInner, Kind};
 use sources::{PathSource, git};
 use util::{CargoResult, Config, internal, ChainError, ToUrl, human};
-use util::{hex, Sha256, paths};
+use util::{hex, network, Sha256, paths, HttpNot200};
 use ops;
 
 static DEFAULT: &'static str = "https://github.com/rust-lang/crates.io-index";
 
+/// The maximum number of `.crate` files downloaded at the same time.
+const MAX_PARALLEL_DOWNLOADS: usize = 8;
+
 pub struct RegistrySource<'cfg> {
     source_id: SourceId,
     checkout_path: PathBuf,
     cache_path: PathBuf,
     src_path: PathBuf,
     config: &'cfg Config,
-    handle: Option<http::Handle>,
     sources: HashMap<PackageId, PathSource<'cfg>>,
     hashes: HashMap<(String, String), String>, // (name, vers) => cksum
     cache: HashMap<String, Vec<(Summary, bool)>>,
@@ -205,6 +211,23 @@ pub struct RegistryConfig {
     pub api: String,
 }
 
+/// A `.crate` file which is missing from the local cache.
+struct Download {
+    id: PackageId,
+    url: Url,
+    dst: PathBuf,
+    cksum: String,
+}
+
+/// Messages sent from download threads back to the thread reporting progress.
+enum Event {
+    /// The download at this index has received this many bytes so far.
+    Progress(usize, u64),
+    /// A spurious error was hit and the download is being retried.
+    Warning(String),
+    Finished(CargoResult<()>),
+}
+
 #[derive(RustcDecodable)]
 struct RegistryPackage {
     name: String,
@@ -224,6 +247,7 @@ struct RegistryDependency {
     default_features: bool,
     target: Option<String>,
     kind: Option<String>,
+    registry: Option<String>,
 }
 
 impl<'cfg> RegistrySource<'cfg> {
@@ -238,7 +262,6 @@ impl<'cfg> RegistrySource<'cfg> {
             src_path: config.registry_source_path().join(&part),
             config: config,
             source_id: source_id.clone(),
-            handle: None,
             sources: HashMap::new(),
             hashes: HashMap::new(),
             cache: HashMap::new(),
@@ -251,11 +274,21 @@ impl<'cfg> RegistrySource<'cfg> {
     /// This is the main cargo registry by default, but it can be overridden in
     /// a .cargo/config
     pub fn url(config: &Config) -> CargoResult<Url> {
-        let config = try!(ops::registry_configuration(config));
+        let config = try!(ops::registry_configuration(config, None));
         let url = config.index.unwrap_or(DEFAULT.to_string());
         url.to_url().map_err(human)
     }
 
+    /// Get the URL of the registry named `name`.
+    ///
+    /// Named registries are configured in the `[registries]` table of a
+    /// .cargo/config, each with its own `index`.
+    pub fn alt_url(config: &Config, name: &str) -> CargoResult<Url> {
+        let config = try!(ops::registry_configuration(config, Some(name)));
+        let url = config.index.expect("named registries always have an index");
+        url.to_url().map_err(human)
+    }
+
     /// Get the default url for the registry
     pub fn default_url() -> String {
         DEFAULT.to_string()
@@ -287,49 +320,86 @@ impl<'cfg> RegistrySource<'cfg> {
         Ok(repo)
     }
 
-    /// Download the given package from the given url into the local cache.
+    /// Download all of `downloads` into the local cache.
     ///
-    /// This will perform the HTTP request to fetch the package. This function
-    /// will only succeed if the HTTP download was successful and the file is
-    /// then ready for inspection.
+    /// Up to `MAX_PARALLEL_DOWNLOADS` crates are fetched at once, each on its
+    /// own thread with its own HTTP handle, while this thread reports
+    /// progress. A crate is only written to the cache once its checksum has
+    /// been verified, so an interrupted or corrupt download never leaves a
+    /// `.crate` file behind.
     ///
-    /// No action is taken if the package is already downloaded.
-    fn download_package(&mut self, pkg: &PackageId, url: &Url)
-                        -> CargoResult<PathBuf> {
-        // TODO: should discover filename from the S3 redirect
-        let filename = format!("{}-{}.crate", pkg.name(), pkg.version());
-        let dst = self.cache_path.join(&filename);
-        if fs::metadata(&dst).is_ok() { return Ok(dst) }
-        try!(self.config.shell().status("Downloading", pkg));
-
-        try!(fs::create_dir_all(dst.parent().unwrap()));
-        let expected_hash = try!(self.hash(pkg));
-        let handle = match self.handle {
-            Some(ref mut handle) => handle,
-            None => {
-                self.handle = Some(try!(ops::http_handle(self.config)));
-                self.handle.as_mut().unwrap()
-            }
-        };
-        // TODO: don't download into memory (curl-rust doesn't expose it)
-        let resp = try!(handle.get(url.to_string()).follow_redirects(true).exec());
-        if resp.get_code() != 200 && resp.get_code() != 0 {
-            return Err(internal(format!("failed to get 200 response from {}\n{}",
-                                        url, resp)))
+    /// The first failure stops any further downloads from being started and
+    /// is returned once the in-flight ones have finished.
+    ///
+    /// Downloads are not streamed to disk. curl-rust 0.2 has no write
+    /// callback and only hands back a response once its whole body has been
+    /// received, so each in-flight crate is held in memory until it has been
+    /// verified and written out.
+    fn download_packages(&self, downloads: &[Download]) -> CargoResult<()> {
+        if downloads.is_empty() { return Ok(()) }
+        for download in downloads.iter() {
+            try!(self.config.shell().status("Downloading", &download.id));
         }
+        try!(fs::create_dir_all(&self.cache_path));
 
-        // Verify what we just downloaded
-        let actual = {
-            let mut state = Sha256::new();
-            state.update(resp.get_body());
-            state.finish()
-        };
-        if actual.to_hex() != expected_hash {
-            bail!("failed to verify the checksum of `{}`", pkg)
+        let retries = try!(network::retries(self.config));
+        let workers = cmp::min(downloads.len(), MAX_PARALLEL_DOWNLOADS);
+        let mut handles = Vec::new();
+        for _ in 0..workers {
+            handles.push(try!(ops::http_handle(self.config)));
         }
 
-        try!(paths::write(&dst, resp.get_body()));
-        Ok(dst)
+        let next = AtomicUsize::new(0);
+        let (tx, rx) = channel();
+        crossbeam::scope(|scope| {
+            for mut handle in handles.into_iter() {
+                let tx = tx.clone();
+                let next = &next;
+                scope.spawn(move || {
+                    loop {
+                        let i = next.fetch_add(1, Ordering::SeqCst);
+                        let download = match downloads.get(i) {
+                            Some(download) => download,
+                            None => break,
+                        };
+                        let res = download.fetch(&mut handle, retries, i, &tx);
+                        if tx.send(Event::Finished(res)).is_err() { break }
+                    }
+                });
+            }
+            drop(tx);
+
+            let mut received = vec![0; downloads.len()];
+            let mut finished = 0;
+            let mut error = None;
+            for event in rx.iter() {
+                match event {
+                    Event::Progress(i, bytes) => received[i] = bytes,
+                    Event::Warning(msg) => {
+                        let mut shell = self.config.shell();
+                        try!(shell.clear_progress());
+                        try!(shell.warn(msg));
+                    }
+                    Event::Finished(Ok(())) => finished += 1,
+                    Event::Finished(Err(e)) => {
+                        finished += 1;
+                        if error.is_none() {
+                            next.store(downloads.len(), Ordering::SeqCst);
+                            error = Some(e);
+                        }
+                    }
+                }
+                let bytes = received.iter().fold(0, |a, b| a + *b);
+                try!(self.config.shell().progress("Downloading", format!(
+                    "{} of {} crates ({})", finished, downloads.len(),
+                    human_bytes(bytes))));
+            }
+            try!(self.config.shell().clear_progress());
+            match error {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        })
     }
 
     /// Return the hash listed for a specified PackageId.
@@ -424,11 +494,19 @@ impl<'cfg> RegistrySource<'cfg> {
     fn parse_registry_dependency(&self, dep: RegistryDependency)
                                  -> CargoResult<Dependency> {
         let RegistryDependency {
-            name, req, features, optional, default_features, target, kind
+            name, req, features, optional, default_features, target, kind,
+            registry,
         } = dep;
 
-        let dep = try!(DependencyInner::parse(&name, Some(&req),
-                                              &self.source_id));
+        // Dependencies on crates from another registry carry the URL of that
+        // registry's index.
+        let source_id = match registry {
+            Some(url) => {
+                SourceId::for_registry(&try!(url.to_url().map_err(human)))
+            }
+            None => self.source_id.clone(),
+        };
+        let dep = try!(DependencyInner::parse(&name, Some(&req), &source_id));
         let kind = match kind.as_ref().map(|s| &s[..]).unwrap_or("") {
             "dev" => Kind::Development,
             "build" => Kind::Build,
@@ -458,10 +536,13 @@ impl<'cfg> RegistrySource<'cfg> {
              format!("registry `{}`", self.source_id.url())));
         let repo = try!(self.open());
 
-        // git fetch origin
+        // git fetch origin master
+        //
+        // The index only ever uses `master`, and fetching into the existing
+        // checkout only transfers what changed since the last update.
         let url = self.source_id.url().to_string();
-        let refspec = "refs/heads/*:refs/remotes/origin/*";
-        try!(git::fetch(&repo, &url, refspec).chain_error(|| {
+        let refspecs = ["refs/heads/master:refs/remotes/origin/master".to_string()];
+        try!(git::fetch(&repo, &url, &refspecs, self.config).chain_error(|| {
             internal(format!("failed to fetch `{}`", url))
         }));
 
@@ -516,6 +597,67 @@ impl<'cfg> Registry for RegistrySource<'cfg> {
     }
 }
 
+impl Download {
+    /// Fetch this crate with `handle`, reporting progress on `tx` as the
+    /// download at index `idx`.
+    fn fetch(&self, handle: &mut http::Handle, retries: u32, idx: usize,
+             tx: &Sender<Event>) -> CargoResult<()> {
+        self.try_fetch(handle, retries, idx, tx).chain_error(|| {
+            internal(format!("failed to download package `{}` from {}",
+                             self.id, self.url))
+        })
+    }
+
+    fn try_fetch(&self, handle: &mut http::Handle, retries: u32, idx: usize,
+                 tx: &Sender<Event>) -> CargoResult<()> {
+        let resp = try!(network::retry(retries, || {
+            let resp = try!(handle.get(self.url.to_string())
+                                  .follow_redirects(true)
+                                  .progress(|_, now, _, _| {
+                                      let _ = tx.send(Event::Progress(idx,
+                                                                      now as u64));
+                                  })
+                                  .exec());
+            if resp.get_code() != 200 && resp.get_code() != 0 {
+                return Err(From::from(HttpNot200 {
+                    code: resp.get_code(),
+                    url: self.url.to_string(),
+                    response: resp.to_string(),
+                }))
+            }
+            Ok(resp)
+        }, |msg| {
+            let _ = tx.send(Event::Warning(msg));
+            Ok(())
+        }));
+
+        // Verify what we just downloaded
+        let actual = {
+            let mut state = Sha256::new();
+            state.update(resp.get_body());
+            state.finish()
+        };
+        if actual.to_hex() != self.cksum {
+            bail!("failed to verify the checksum of `{}`", self.id)
+        }
+
+        // Write to a temporary file first so a partially written crate never
+        // shows up in the cache.
+        let part = self.dst.with_extension("crate.part");
+        try!(paths::write(&part, resp.get_body()));
+        try!(fs::rename(&part, &self.dst));
+        Ok(())
+    }
+}
+
+fn human_bytes(bytes: u64) -> String {
+    if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
 impl<'cfg> Source for RegistrySource<'cfg> {
     fn update(&mut self) -> CargoResult<()> {
         // If we have an imprecise version then we don't know what we're going
@@ -534,24 +676,43 @@ impl<'cfg> Source for RegistrySource<'cfg> {
     fn download(&mut self, packages: &[PackageId]) -> CargoResult<()> {
         let config = try!(self.config());
         let url = try!(config.dl.to_url().map_err(internal));
+        let mut wanted = Vec::new();
+        let mut downloads = Vec::new();
         for package in packages.iter() {
             if self.source_id != *package.source_id() { continue }
             if self.sources.contains_key(package) { continue }
+            if wanted.contains(package) { continue }
+            wanted.push(package.clone());
+
+            // TODO: should discover filename from the S3 redirect
+            let filename = format!("{}-{}.crate", package.name(),
+                                   package.version());
+            let dst = self.cache_path.join(&filename);
+            if fs::metadata(&dst).is_ok() { continue }
 
             let mut url = url.clone();
             url.path_mut().unwrap().push(package.name().to_string());
             url.path_mut().unwrap().push(package.version().to_string());
             url.path_mut().unwrap().push("download".to_string());
-            let path = try!(self.download_package(package, &url).chain_error(|| {
-                internal(format!("failed to download package `{}` from {}",
-                                 package, url))
-            }));
-            let path = try!(self.unpack_package(package, path).chain_error(|| {
+            downloads.push(Download {
+                cksum: try!(self.hash(package)),
+                id: package.clone(),
+                url: url,
+                dst: dst,
+            });
+        }
+        try!(self.download_packages(&downloads));
+
+        for package in wanted.into_iter() {
+            let filename = format!("{}-{}.crate", package.name(),
+                                   package.version());
+            let tarball = self.cache_path.join(&filename);
+            let path = try!(self.unpack_package(&package, tarball).chain_error(|| {
                 internal(format!("failed to unpack package `{}`", package))
             }));
             let mut src = PathSource::new(&path, &self.source_id, self.config);
             try!(src.update());
-            self.sources.insert(package.clone(), src);
+            self.sources.insert(package, src);
         }
         Ok(())
     }
@@ -4,7 +4,7 @@ use std::collections::hash_map::{HashMap};
 use std::env;
 use std::ffi::OsString;
 use std::fmt;
-use std::fs::{self, File};
+use std::fs::{self, File, OpenOptions};
 use std::io::prelude::*;
 use std::mem;
 use std::path::{Path, PathBuf};
@@ -66,8 +66,30 @@ impl Config {
         Config::new(shell, cwd, homedir)
     }
 
+    /// Creates the configuration for an external subcommand run as
+    /// `cargo <name>`.
+    ///
+    /// This is `Config::default` with the shell set up the way Cargo's own
+    /// was, which Cargo passes along in the `CARGO_TERM_VERBOSE`,
+    /// `CARGO_TERM_QUIET` and `CARGO_TERM_COLOR` environment variables.
+    /// `CARGO_HOME` is set as well, so the same configuration files are read.
+    pub fn for_subcommand() -> CargoResult<Config> {
+        let cfg = try!(Config::default());
+        let verbose = try!(cfg.get_env_bool("term.verbose")).unwrap_or(false);
+        let quiet = try!(cfg.get_env_bool("term.quiet")).unwrap_or(false);
+        try!(cfg.shell().set_verbosity(verbose, quiet));
+        if let Some(CV::String(color, _)) = try!(cfg.get_env("term.color")) {
+            try!(cfg.shell().set_color_config(Some(&color)));
+        }
+        Ok(cfg)
+    }
+
     pub fn home(&self) -> &Path { &self.home_path }
 
+    pub fn credentials_path(&self) -> PathBuf {
+        self.home_path.join("credentials")
+    }
+
     pub fn git_db_path(&self) -> PathBuf {
         self.home_path.join("git").join("db")
     }
@@ -118,7 +140,96 @@ impl Config {
         *self.target_dir.borrow_mut() = Some(path.to_owned());
     }
 
+    /// Look up the configuration value for a dotted `key` like `build.jobs`.
+    ///
+    /// An environment variable named after the key, `CARGO_BUILD_JOBS` in
+    /// this case, overrides whatever the configuration files say. Such values
+    /// are always strings; the typed getters below convert them as needed.
+    ///
+    /// Looking up a table from the configuration files, such as `build`,
+    /// includes the variables for each of its keys as well. Use `get_table`
+    /// for tables which may only be defined in the environment.
     pub fn get(&self, key: &str) -> CargoResult<Option<ConfigValue>> {
+        if let Some(val) = try!(self.get_env(key)) {
+            return Ok(Some(val))
+        }
+        match try!(self.get_file(key)) {
+            Some(CV::Table(table, path)) => {
+                let table = try!(self.env_table(key, table));
+                Ok(Some(CV::Table(table, path)))
+            }
+            val => Ok(val),
+        }
+    }
+
+    /// Apply the environment variables for the keys of the table at `key` to
+    /// `table`.
+    ///
+    /// Keys the table already has are overridden by their own variable. Any
+    /// other variable under the table's prefix adds a string, named after the
+    /// rest of the variable in lowercase with underscores as dashes, so
+    /// `CARGO_ALIAS_B` defines `alias.b`.
+    fn env_table(&self, key: &str, mut table: HashMap<String, CV>)
+                 -> CargoResult<HashMap<String, CV>> {
+        let mut subtables = Vec::new();
+        for (k, v) in table.iter_mut() {
+            let subkey = format!("{}.{}", key, k);
+            let new = match *v {
+                CV::Table(ref map, ref path) => {
+                    subtables.push(format!("{}_", env_key(&subkey)));
+                    CV::Table(try!(self.env_table(&subkey, map.clone())),
+                              path.clone())
+                }
+                _ => match try!(self.get_env(&subkey)) {
+                    Some(val) => val,
+                    None => continue,
+                },
+            };
+            *v = new;
+        }
+
+        let prefix = format!("{}_", env_key(key));
+        for (var, val) in env::vars_os() {
+            let (var, val) = match (var.into_string(), val.into_string()) {
+                (Ok(var), Ok(val)) => (var, val),
+                _ => continue,
+            };
+            if !var.starts_with(&prefix) ||
+               subtables.iter().any(|t| var.starts_with(t)) {
+                continue
+            }
+            let name = var[prefix.len()..].to_lowercase().replace("_", "-");
+            if table.keys().any(|k| env_key(&format!("{}.{}", key, k)) == var) {
+                continue
+            }
+            table.insert(name, CV::String(val, env_definition(&var)));
+        }
+        Ok(table)
+    }
+
+    fn get_env(&self, key: &str) -> CargoResult<Option<ConfigValue>> {
+        let var = env_key(key);
+        match env::var(&var) {
+            Ok(val) => Ok(Some(CV::String(val, env_definition(&var)))),
+            Err(env::VarError::NotPresent) => Ok(None),
+            Err(env::VarError::NotUnicode(..)) => {
+                bail!("environment variable `{}` is not valid unicode", var)
+            }
+        }
+    }
+
+    fn get_env_bool(&self, key: &str) -> CargoResult<Option<bool>> {
+        match try!(self.get_env(key)) {
+            Some(CV::String(ref s, _)) if s == "true" => Ok(Some(true)),
+            Some(CV::String(ref s, _)) if s == "false" => Ok(Some(false)),
+            Some(CV::String(ref s, ref path)) => {
+                self.invalid_env(key, "`true` or `false`", s, path)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn get_file(&self, key: &str) -> CargoResult<Option<ConfigValue>> {
         let vals = try!(self.values());
         let mut parts = key.split('.').enumerate();
         let mut val = match vals.get(parts.next().unwrap().1) {
@@ -159,70 +270,155 @@ impl Config {
 
     pub fn get_path(&self, key: &str) -> CargoResult<Option<PathBuf>> {
         if let Some((specified_path, path_to_config)) = try!(self.get_string(&key)) {
-            if specified_path.contains("/") || (cfg!(windows) && specified_path.contains("\\")) {
-                // An absolute or a relative path
-                let prefix_path = path_to_config.parent().unwrap().parent().unwrap();
-                // Joining an absolute path to any path results in the given absolute path
-                Ok(Some(prefix_path.join(specified_path)))
-            } else {
-                // A pathless name
-                Ok(Some(PathBuf::from(specified_path)))
-            }
+            Ok(Some(self.string_to_path(specified_path, &path_to_config)))
         } else {
             Ok(None)
         }
     }
 
+    /// Get a program and the arguments to run it with, written either as a
+    /// whitespace-separated string or as a list. The program is resolved like
+    /// `get_path` resolves paths.
+    pub fn get_path_and_args(&self, key: &str)
+                             -> CargoResult<Option<(PathBuf, Vec<String>)>> {
+        let (mut args, definition) = match try!(self.get(key)) {
+            Some(CV::String(s, path)) => {
+                (s.split_whitespace().map(|s| s.to_string()).collect(), path)
+            }
+            Some(CV::List(list, path)) => {
+                (list.into_iter().map(|(s, _)| s).collect::<Vec<_>>(), path)
+            }
+            Some(val) => return self.expected("string or list", key, val),
+            None => return Ok(None),
+        };
+        if args.is_empty() {
+            bail!("`{}` in {} must name a program to run", key,
+                  definition.display())
+        }
+        let program = args.remove(0);
+        Ok(Some((self.string_to_path(program, &definition), args)))
+    }
+
+    fn string_to_path(&self, specified_path: String, definition: &Path)
+                      -> PathBuf {
+        if specified_path.contains("/") || (cfg!(windows) && specified_path.contains("\\")) {
+            // An absolute or a relative path
+            let prefix_path = self.definition_root(definition);
+            // Joining an absolute path to any path results in the given absolute path
+            prefix_path.join(specified_path)
+        } else {
+            // A pathless name
+            PathBuf::from(specified_path)
+        }
+    }
+
     pub fn get_list(&self, key: &str) -> CargoResult<Option<(Vec<(String, PathBuf)>, PathBuf)>> {
         match try!(self.get(key)) {
             Some(CV::List(i, path)) => Ok(Some((i, path))),
+            Some(CV::String(ref s, ref path)) if is_env_definition(path) => {
+                let list = s.split_whitespace().map(|s| {
+                    (s.to_string(), path.clone())
+                }).collect();
+                Ok(Some((list, path.clone())))
+            }
             Some(val) => self.expected("list", key, val),
             None => Ok(None),
         }
     }
 
+    /// Look up the table at `key`, along with any environment variables for
+    /// its keys. `CARGO_ALIAS_B` alone is enough to define an `[alias]` table,
+    /// for example.
     pub fn get_table(&self, key: &str)
                     -> CargoResult<Option<(HashMap<String, CV>, PathBuf)>> {
         match try!(self.get(key)) {
             Some(CV::Table(i, path)) => Ok(Some((i, path))),
             Some(val) => self.expected("table", key, val),
-            None => Ok(None),
+            None => {
+                // Whether a key is a table can't be told from the variable
+                // alone, so only tables are created from the environment.
+                let table = try!(self.env_table(key, HashMap::new()));
+                if table.is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some((table, env_definition(&env_key(key)))))
+                }
+            }
         }
     }
 
     pub fn get_i64(&self, key: &str) -> CargoResult<Option<(i64, PathBuf)>> {
         match try!(self.get(key)) {
             Some(CV::Integer(i, path)) => Ok(Some((i, path))),
+            Some(CV::String(ref s, ref path)) if is_env_definition(path) => {
+                match s.trim().parse() {
+                    Ok(i) => Ok(Some((i, path.clone()))),
+                    Err(..) => self.invalid_env(key, "an integer", s, path),
+                }
+            }
             Some(val) => self.expected("integer", key, val),
             None => Ok(None),
         }
     }
 
+    pub fn get_bool(&self, key: &str) -> CargoResult<Option<(bool, PathBuf)>> {
+        match try!(self.get(key)) {
+            Some(CV::Boolean(b, path)) => Ok(Some((b, path))),
+            Some(CV::String(ref s, ref path)) if is_env_definition(path) => {
+                match &s[..] {
+                    "true" => Ok(Some((true, path.clone()))),
+                    "false" => Ok(Some((false, path.clone()))),
+                    _ => self.invalid_env(key, "`true` or `false`", s, path),
+                }
+            }
+            Some(val) => self.expected("bool", key, val),
+            None => Ok(None),
+        }
+    }
+
     pub fn expected<T>(&self, ty: &str, key: &str, val: CV) -> CargoResult<T> {
         val.expected(ty).map_err(|e| {
             human(format!("invalid configuration for key `{}`\n{}", key, e))
         })
     }
 
+    fn invalid_env<T>(&self, key: &str, wanted: &str, val: &str,
+                      definition: &Path) -> CargoResult<T> {
+        Err(human(format!("invalid configuration for key `{}`\n\
+                           expected {}, but found `{}` in {}", key, wanted,
+                          val, definition.display())))
+    }
+
+    /// The directory which relative paths are resolved against for a value
+    /// defined at `definition`.
+    ///
+    /// For a configuration file that's the directory containing its `.cargo`
+    /// directory, and for an environment variable the current directory.
+    pub fn definition_root(&self, definition: &Path) -> PathBuf {
+        if is_env_definition(definition) {
+            self.cwd.clone()
+        } else {
+            definition.parent().unwrap().parent().unwrap().to_path_buf()
+        }
+    }
+
     fn load_values(&self) -> CargoResult<()> {
         let mut cfg = CV::Table(HashMap::new(), PathBuf::from("."));
 
-        try!(walk_tree(&self.cwd, |mut file, path| {
-            let mut contents = String::new();
-            try!(file.read_to_string(&mut contents));
-            let table = try!(cargo_toml::parse(&contents, &path).chain_error(|| {
-                human(format!("could not parse TOML configuration in `{}`",
-                              path.display()))
+        // Values are merged first-come-first-served, so loading the
+        // credentials file first lets a token saved by `cargo login` take
+        // precedence over one which is still sitting in a config file.
+        let credentials = self.credentials_path();
+        if fs::metadata(&credentials).is_ok() {
+            let file = try!(File::open(&credentials));
+            try!(merge_file(&mut cfg, file, &credentials).chain_error(|| {
+                human("Couldn't load Cargo credentials")
             }));
-            let toml = toml::Value::Table(table);
-            let value = try!(CV::from_toml(&path, toml).chain_error(|| {
-                human(format!("failed to load TOML configuration from `{}`",
-                              path.display()))
-            }));
-            try!(cfg.merge(value));
-            Ok(())
-        }).chain_error(|| human("Couldn't load Cargo configuration")));
+        }
 
+        try!(walk_tree(&self.cwd, |file, path| {
+            merge_file(&mut cfg, file, path)
+        }).chain_error(|| human("Couldn't load Cargo configuration")));
 
         *self.values.borrow_mut() = match cfg {
             CV::Table(map, _) => map,
@@ -243,11 +439,8 @@ impl Config {
     }
 
     fn scrape_target_dir_config(&mut self) -> CargoResult<()> {
-        if let Some((dir, dir2)) = try!(self.get_string("build.target-dir")) {
-            let mut path = PathBuf::from(dir2);
-            path.pop();
-            path.pop();
-            path.push(dir);
+        if let Some((dir, definition)) = try!(self.get_string("build.target-dir")) {
+            let path = self.definition_root(&definition).join(dir);
             *self.target_dir.borrow_mut() = Some(path);
         } else if let Some(dir) = env::var_os("CARGO_TARGET_DIR") {
             *self.target_dir.borrow_mut() = Some(self.cwd.join(dir));
@@ -320,7 +513,7 @@ impl Encodable for ConfigValue {
 }
 
 impl ConfigValue {
-    fn from_toml(path: &Path, toml: toml::Value) -> CargoResult<ConfigValue> {
+    pub fn from_toml(path: &Path, toml: toml::Value) -> CargoResult<ConfigValue> {
         match toml {
             toml::Value::String(val) => Ok(CV::String(val, path.to_path_buf())),
             toml::Value::Boolean(b) => Ok(CV::Boolean(b, path.to_path_buf())),
@@ -448,7 +641,7 @@ impl ConfigValue {
                              self.definition_path().display())))
     }
 
-    fn into_toml(self) -> toml::Value {
+    pub fn into_toml(self) -> toml::Value {
         match self {
             CV::Boolean(s, _) => toml::Value::Boolean(s),
             CV::String(s, _) => toml::Value::String(s),
@@ -464,6 +657,30 @@ impl ConfigValue {
     }
 }
 
+/// Name of the environment variable which overrides `key`, e.g.
+/// `CARGO_HTTP_TIMEOUT` for `http.timeout`.
+fn env_key(key: &str) -> String {
+    let key = key.chars().flat_map(|c| {
+        match c {
+            '.' | '-' => '_',
+            c => c,
+        }.to_uppercase()
+    }).collect::<String>();
+    format!("CARGO_{}", key)
+}
+
+const ENV_DEFINITION: &'static str = "environment variable ";
+
+/// The definition path recorded for a value read from the environment, so
+/// messages about it read "... in environment variable `CARGO_FOO`".
+fn env_definition(var: &str) -> PathBuf {
+    PathBuf::from(format!("{}`{}`", ENV_DEFINITION, var))
+}
+
+fn is_env_definition(path: &Path) -> bool {
+    path.to_str().map(|s| s.starts_with(ENV_DEFINITION)).unwrap_or(false)
+}
+
 fn homedir(cwd: &Path) -> Option<PathBuf> {
     let cargo_home = env::var_os("CARGO_HOME").map(|home| {
         cwd.join(home)
@@ -472,6 +689,22 @@ fn homedir(cwd: &Path) -> Option<PathBuf> {
     return cargo_home.or(user_home);
 }
 
+fn merge_file(cfg: &mut ConfigValue, mut file: File,
+              path: &Path) -> CargoResult<()> {
+    let mut contents = String::new();
+    try!(file.read_to_string(&mut contents));
+    let table = try!(cargo_toml::parse(&contents, &path).chain_error(|| {
+        human(format!("could not parse TOML configuration in `{}`",
+                      path.display()))
+    }));
+    let toml = toml::Value::Table(table);
+    let value = try!(CV::from_toml(&path, toml).chain_error(|| {
+        human(format!("failed to load TOML configuration from `{}`",
+                      path.display()))
+    }));
+    cfg.merge(value)
+}
+
 fn walk_tree<F>(pwd: &Path, mut walk: F) -> CargoResult<()>
     where F: FnMut(File, &Path) -> CargoResult<()>
 {
@@ -508,23 +741,284 @@ fn walk_tree<F>(pwd: &Path, mut walk: F) -> CargoResult<()>
     Ok(())
 }
 
+/// Set `key` to `value` in the configuration file for `loc`, returning the
+/// path of that file.
+///
+/// The file is edited line by line rather than rewritten, so its comments
+/// and ordering are preserved. An existing definition of the key is replaced
+/// in place, and otherwise the key is added to the end of its table, which is
+/// created if it doesn't exist yet.
 pub fn set_config(cfg: &Config, loc: Location, key: &str,
-                  value: ConfigValue) -> CargoResult<()> {
-    // TODO: There are a number of drawbacks here
-    //
-    // 1. Project is unimplemented
-    // 2. This blows away all comments in a file
-    // 3. This blows away the previous ordering of a file.
+                  value: ConfigValue) -> CargoResult<PathBuf> {
     let file = match loc {
         Location::Global => cfg.home_path.join("config"),
-        Location::Project => unimplemented!(),
+        Location::Project => cfg.cwd.join(".cargo").join("config"),
     };
+    if let CV::Table(..) = value {
+        bail!("only strings, integers, booleans and lists can be set, not \
+               tables")
+    }
+    try!(fs::create_dir_all(file.parent().unwrap()));
+    let contents = paths::read(&file).unwrap_or(String::new());
+    try!(cargo_toml::parse(&contents, &file));
+
+    let mut parts = key.split('.').collect::<Vec<_>>();
+    let last = parts.pop().unwrap();
+    let value = value.into_toml();
+    let line = format!("{} = {}", toml_key(last), value);
+    let new_contents = set_line(&contents, &parts, last, &line);
+
+    // Line-based editing can't cope with everything TOML allows, such as
+    // arrays spanning several lines or inline tables, so make sure the result
+    // actually says what was asked for before writing it out.
+    let mut current = cargo_toml::parse(&new_contents, &file).ok()
+                                 .map(toml::Value::Table);
+    for part in key.split('.') {
+        current = match current {
+            Some(toml::Value::Table(mut table)) => table.remove(part),
+            _ => None,
+        };
+    }
+    if current != Some(value) {
+        bail!("could not update `{}` in {} without disturbing the rest of \
+               the file, it needs to be edited by hand", key, file.display())
+    }
+
+    try!(paths::write(&file, new_contents.as_bytes()));
+    Ok(file)
+}
+
+/// Replace the definition of `key` in `table` with `line`, or add `line` to
+/// the end of that table. A comment at the end of the replaced line is kept.
+fn set_line(contents: &str, table: &[&str], key: &str, line: &str) -> String {
+    let mut lines = contents.lines().map(|l| l.to_string()).collect::<Vec<_>>();
+    // Keys before the first header belong to the top-level table
+    let mut in_table = table.is_empty();
+    let mut insert_at = if in_table {Some(0)} else {None};
+    let mut existing = None;
+    for (i, l) in lines.iter().enumerate() {
+        let trimmed = l.trim();
+        if trimmed.starts_with("[[") {
+            in_table = false;
+            continue
+        }
+        if let Some(header) = parse_header(trimmed) {
+            in_table = header.len() == table.len() &&
+                       header.iter().zip(table.iter()).all(|(a, b)| a == b);
+            if in_table {
+                insert_at = Some(i + 1);
+            }
+            continue
+        }
+        if !in_table || trimmed.is_empty() || trimmed.starts_with("#") {
+            continue
+        }
+        let defined = trimmed.find('=').map(|eq| {
+            trimmed[..eq].trim().trim_matches('"')
+        });
+        if defined == Some(key) {
+            let indent = &l[..l.len() - l.trim_left().len()];
+            let comment = trailing_comment(l).unwrap_or("");
+            existing = Some((i, format!("{}{}{}", indent, line, comment)));
+            break
+        }
+        insert_at = Some(i + 1);
+    }
+
+    match (existing, insert_at) {
+        (Some((i, replacement)), _) => lines[i] = replacement,
+        (None, Some(i)) => lines.insert(i, line.to_string()),
+        (None, None) => {
+            if lines.last().map(|l| !l.trim().is_empty()).unwrap_or(false) {
+                lines.push(String::new());
+            }
+            let header = table.iter().map(|p| toml_key(p))
+                              .collect::<Vec<_>>().join(".");
+            lines.push(format!("[{}]", header));
+            lines.push(line.to_string());
+        }
+    }
+    join_lines(&lines)
+}
+
+/// The comment at the end of a `key = value` line, along with the whitespace
+/// separating it from the value.
+fn trailing_comment(line: &str) -> Option<&str> {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' && q == '"' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => {
+                let start = line[..i].trim_right().len();
+                return Some(&line[start..])
+            }
+            None => {}
+        }
+    }
+    None
+}
+
+/// The dotted key of a `[table]` header line, if `line` is one.
+fn parse_header(line: &str) -> Option<Vec<String>> {
+    if !line.starts_with("[") { return None }
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut quoted = false;
+    for c in line[1..].chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '.' if !quoted => {
+                parts.push(part.trim().to_string());
+                part = String::new();
+            }
+            ']' if !quoted => {
+                parts.push(part.trim().to_string());
+                return Some(parts)
+            }
+            c => part.push(c),
+        }
+    }
+    None
+}
+
+fn toml_key(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| {
+        c.is_alphanumeric() || c == '-' || c == '_'
+    }) {
+        key.to_string()
+    } else {
+        format!("{:?}", key)
+    }
+}
+
+fn join_lines(lines: &[String]) -> String {
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    contents
+}
+
+/// Stores a registry token in the credentials file, or removes it when
+/// `token` is `None`.
+///
+/// `key` names the table holding the token, e.g. `registry` or
+/// `registries.foo`. Returns whether a token was previously saved there.
+pub fn save_credentials(cfg: &Config, key: &str,
+                        token: Option<String>) -> CargoResult<bool> {
+    let file = cfg.credentials_path();
     try!(fs::create_dir_all(file.parent().unwrap()));
     let contents = paths::read(&file).unwrap_or(String::new());
     let mut toml = try!(cargo_toml::parse(&contents, &file));
-    toml.insert(key.to_string(), value.into_toml());
+    let token_removed = token.is_none();
+    let existed = {
+        let mut table = &mut toml;
+        for part in key.split('.') {
+            let entry = {table}.entry(part.to_string()).or_insert_with(|| {
+                toml::Value::Table(toml::Table::new())
+            });
+            table = match *entry {
+                toml::Value::Table(ref mut table) => table,
+                _ => bail!("expected `{}` in {} to be a table", part,
+                           file.display()),
+            };
+        }
+        match token {
+            Some(token) => {
+                table.insert("token".to_string(), toml::Value::String(token))
+            }
+            None => table.remove("token"),
+        }.is_some()
+    };
+    if !existed && token_removed {
+        return Ok(false)
+    }
 
+    // The file holds secrets, so make sure nobody else can read it before
+    // anything is written into it.
+    let mut f = try!(open_private(&file));
     let contents = toml::Value::Table(toml).to_string();
-    try!(paths::write(&file, contents.as_bytes()));
-    Ok(())
+    try!(f.write_all(contents.as_bytes()).chain_error(|| {
+        internal(format!("failed to write `{}`", file.display()))
+    }));
+    Ok(existed)
+}
+
+/// Opens `path` for writing, truncated and readable only by its owner.
+#[cfg(unix)]
+fn open_private(path: &Path) -> CargoResult<File> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    let f = try!(OpenOptions::new().write(true).create(true).truncate(true)
+                                   .mode(0o600).open(path));
+    // The mode only applies to a newly created file, so an existing one is
+    // locked down as well while it's still empty.
+    try!(fs::set_permissions(path, fs::Permissions::from_mode(0o600)));
+    Ok(f)
+}
+
+#[cfg(not(unix))]
+fn open_private(path: &Path) -> CargoResult<File> {
+    Ok(try!(OpenOptions::new().write(true).create(true).truncate(true)
+                              .open(path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{set_line, trailing_comment};
+
+    #[test]
+    fn set_line_keeps_the_rest_of_the_file() {
+        let contents = "\
+# top comment
+[build]
+  jobs = 1 # old
+
+[net]
+retry = 2
+";
+        assert_eq!(set_line(contents, &["build"], "jobs", "jobs = 4"), "\
+# top comment
+[build]
+  jobs = 4 # old
+
+[net]
+retry = 2
+");
+        assert_eq!(set_line(contents, &["build"], "target", "target = \"x\""), "\
+# top comment
+[build]
+  jobs = 1 # old
+target = \"x\"
+
+[net]
+retry = 2
+");
+        assert_eq!(set_line(contents, &["term"], "verbose", "verbose = true"), "\
+# top comment
+[build]
+  jobs = 1 # old
+
+[net]
+retry = 2
+
+[term]
+verbose = true
+");
+    }
+
+    #[test]
+    fn trailing_comment_skips_strings() {
+        assert_eq!(trailing_comment("a = 1  # one"), Some("  # one"));
+        assert_eq!(trailing_comment("a = \"#x\" # s"), Some(" # s"));
+        assert_eq!(trailing_comment("a = '#x'"), None);
+        assert_eq!(trailing_comment("a = \"\\\"#\""), None);
+    }
 }
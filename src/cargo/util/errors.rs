@@ -10,6 +10,7 @@ use rustc_serialize::json;
 
 use curl;
 use git2;
+use registry;
 use toml;
 use url;
 
@@ -21,6 +22,10 @@ pub type CargoResult<T> = Result<T, Box<CargoError>>;
 pub trait CargoError: Error + Send + 'static {
     fn is_human(&self) -> bool { false }
     fn cargo_cause(&self) -> Option<&CargoError>{ None }
+
+    /// Whether this error may have been caused by a transient network
+    /// failure, in which case retrying the operation could succeed.
+    fn maybe_spurious(&self) -> bool { false }
 }
 
 impl Error for Box<CargoError> {
@@ -31,6 +36,7 @@ impl Error for Box<CargoError> {
 impl CargoError for Box<CargoError> {
     fn is_human(&self) -> bool { (**self).is_human() }
     fn cargo_cause(&self) -> Option<&CargoError> { (**self).cargo_cause() }
+    fn maybe_spurious(&self) -> bool { (**self).maybe_spurious() }
 }
 
 // =============================================================================
@@ -238,6 +244,37 @@ impl<E: CargoError> CargoError for Human<E> {
     fn cargo_cause(&self) -> Option<&CargoError> { self.0.cargo_cause() }
 }
 
+// =============================================================================
+// HTTP errors
+
+/// A request which didn't receive a successful response.
+pub struct HttpNot200 {
+    pub code: u32,
+    pub url: String,
+    pub response: String,
+}
+
+impl Error for HttpNot200 {
+    fn description(&self) -> &str { "failed to get a 200 response" }
+}
+
+impl fmt::Display for HttpNot200 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to get 200 response from {}\n{}", self.url,
+               self.response)
+    }
+}
+
+impl fmt::Debug for HttpNot200 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl CargoError for HttpNot200 {
+    fn maybe_spurious(&self) -> bool { self.code >= 500 }
+}
+
 // =============================================================================
 // CLI errors
 
@@ -303,6 +340,8 @@ from_error! {
     json::DecoderError,
     json::EncoderError,
     curl::ErrCode,
+    registry::Error,
+    HttpNot200,
     CliError,
     toml::Error,
     url::ParseError,
@@ -316,10 +355,58 @@ impl<E: CargoError> From<Human<E>> for Box<CargoError> {
 
 impl CargoError for semver::ReqParseError {}
 impl CargoError for io::Error {}
-impl CargoError for git2::Error {}
+impl CargoError for git2::Error {
+    fn maybe_spurious(&self) -> bool {
+        match self.class() {
+            git2::ErrorClass::Net => {}
+            _ => return false,
+        }
+        match self.code() {
+            git2::ErrorCode::Auth | git2::ErrorCode::Certificate => false,
+            // libgit2 reports unexpected HTTP responses as generic network
+            // errors, so the status code has to be read out of the message.
+            // Only server errors are worth retrying, not 401s or 404s.
+            _ => match http_status(self.message()) {
+                Some(code) => code >= 500,
+                None => true,
+            },
+        }
+    }
+}
+
+/// Finds the status code in a message such as `unexpected HTTP status code:
+/// 404`.
+fn http_status(msg: &str) -> Option<u32> {
+    let prefix = "status code: ";
+    msg.find(prefix).and_then(|i| {
+        msg[i + prefix.len()..].split(|c: char| !c.is_digit(10)).next()
+    }).and_then(|code| code.parse().ok())
+}
 impl CargoError for json::DecoderError {}
 impl CargoError for json::EncoderError {}
-impl CargoError for curl::ErrCode {}
+impl CargoError for curl::ErrCode {
+    fn maybe_spurious(&self) -> bool {
+        match self.0 {
+            curl::ffi::CURLcode::CURLE_COULDNT_CONNECT |
+            curl::ffi::CURLcode::CURLE_COULDNT_RESOLVE_PROXY |
+            curl::ffi::CURLcode::CURLE_COULDNT_RESOLVE_HOST |
+            curl::ffi::CURLcode::CURLE_OPERATION_TIMEDOUT |
+            curl::ffi::CURLcode::CURLE_SEND_ERROR |
+            curl::ffi::CURLcode::CURLE_RECV_ERROR |
+            curl::ffi::CURLcode::CURLE_GOT_NOTHING => true,
+            _ => false,
+        }
+    }
+}
+impl CargoError for registry::Error {
+    fn maybe_spurious(&self) -> bool {
+        match *self {
+            registry::Error::Curl(ref e) => e.maybe_spurious(),
+            registry::Error::NotOkResponse(ref resp) => resp.get_code() >= 500,
+            _ => false,
+        }
+    }
+}
 impl CargoError for ProcessError {}
 impl CargoError for CargoTestError {}
 impl CargoError for CliError {}
@@ -0,0 +1,87 @@
+//! Retrying of network operations which fail for spurious reasons.
+
+use std::cmp;
+use std::thread;
+
+use util::{CargoError, CargoResult, Config};
+
+/// How long to wait before the first retry, in milliseconds. The delay
+/// doubles after each attempt up to `MAX_DELAY_MS`.
+const INITIAL_DELAY_MS: u32 = 500;
+const MAX_DELAY_MS: u32 = 10_000;
+
+/// Runs `callback`, retrying it with exponential backoff for as long as it
+/// fails with errors which look like spurious network failures.
+///
+/// The number of retries is configured by `net.retry` and defaults to 2.
+/// Errors such as authentication failures or missing resources are returned
+/// immediately.
+pub fn with_retry<T, F>(config: &Config, callback: F) -> CargoResult<T>
+    where F: FnMut() -> CargoResult<T>
+{
+    let retries = try!(retries(config));
+    retry(retries, callback, |msg| {
+        try!(config.shell().warn(msg));
+        Ok(())
+    })
+}
+
+/// The same as `with_retry`, but for callers which can't reach the shell
+/// themselves, such as worker threads.
+///
+/// Each warning about a retried error is handed to `notify` instead of being
+/// printed.
+pub fn retry<T, F, N>(retries: u32, mut callback: F, mut notify: N)
+                      -> CargoResult<T>
+    where F: FnMut() -> CargoResult<T>,
+          N: FnMut(String) -> CargoResult<()>
+{
+    let mut attempt = 1;
+    loop {
+        match callback() {
+            Err(e) => {
+                if !try!(should_retry(retries, attempt, &*e, &mut notify)) {
+                    return Err(e)
+                }
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Decides whether an operation should be tried again after its `attempt`th
+/// failure, counting from 1, failed with `err`.
+///
+/// If so, the retry is reported to `notify` and this sleeps for the backoff
+/// delay before returning. This is the building block of `retry` for callers
+/// which run their own loop, such as `crates_io::Registry`.
+#[allow(deprecated)] // sleep_ms => sleep in 1.4
+pub fn should_retry<N>(retries: u32, attempt: u32, err: &CargoError,
+                       mut notify: N) -> CargoResult<bool>
+    where N: FnMut(String) -> CargoResult<()>
+{
+    if !err.maybe_spurious() || attempt > retries {
+        return Ok(false)
+    }
+    try!(notify(format!("warning: spurious network error ({} tries \
+                         remaining): {}", retries - attempt + 1, err)));
+    let mut delay = INITIAL_DELAY_MS;
+    for _ in 1..attempt {
+        delay = cmp::min(delay * 2, MAX_DELAY_MS);
+    }
+    thread::sleep_ms(delay);
+    Ok(true)
+}
+
+/// Returns the number of times a spurious network error is retried, as
+/// configured by `net.retry`.
+pub fn retries(config: &Config) -> CargoResult<u32> {
+    match try!(config.get_i64("net.retry")) {
+        Some((n, _)) if n >= 0 => Ok(n as u32),
+        Some((_, path)) => {
+            bail!("`net.retry` in {} must not be negative", path.display())
+        }
+        None => Ok(2),
+    }
+}
@@ -179,6 +179,76 @@ pub fn parse(toml: &str, file: &Path) -> CargoResult<toml::Table> {
     Err(human(error_str))
 }
 
+/// Rewrites a manifest into the form which is placed inside a packaged
+/// `.crate` file.
+///
+/// Once uploaded, `path` keys on dependencies no longer point anywhere
+/// meaningful, so they are stripped out. Dev-dependencies which only have a
+/// `path` cannot be resolved from a registry at all and are dropped, while
+/// any other dependency without a `version` is an error. Registry names are
+/// only known to the local configuration, so `registry` keys are replaced by
+/// the `registry-index` they stand for.
+pub fn normalize_manifest(contents: &str, file: &Path, config: &Config)
+                          -> CargoResult<String> {
+    let mut root = try!(parse(contents, file));
+    try!(normalize_dependency_tables(&mut root, config));
+    if let Some(&mut toml::Value::Table(ref mut platforms)) =
+            root.get_mut("target") {
+        for platform in platforms.values_mut() {
+            if let toml::Value::Table(ref mut platform) = *platform {
+                try!(normalize_dependency_tables(platform, config));
+            }
+        }
+    }
+
+    Ok(format!("# This file was generated by `cargo package` from the \
+                original manifest,\n# which is preserved as \
+                `Cargo.toml.orig`.\n\n{}", toml::Value::Table(root)))
+}
+
+fn normalize_dependency_tables(table: &mut toml::Table, config: &Config)
+                               -> CargoResult<()> {
+    let kinds = [("dependencies", false),
+                 ("build-dependencies", false),
+                 ("build_dependencies", false),
+                 ("dev-dependencies", true),
+                 ("dev_dependencies", true)];
+    for &(key, dev) in kinds.iter() {
+        if let Some(&mut toml::Value::Table(ref mut deps)) = table.get_mut(key) {
+            try!(normalize_deps(deps, dev, config));
+        }
+    }
+    return Ok(());
+
+    fn normalize_deps(deps: &mut toml::Table, dev: bool, config: &Config)
+                      -> CargoResult<()> {
+        let mut path_only = Vec::new();
+        for (name, dep) in deps.iter_mut() {
+            let dep = match *dep {
+                toml::Value::Table(ref mut dep) => dep,
+                _ => continue,
+            };
+            if dep.remove("path").is_some() && !dep.contains_key("version") {
+                if !dev {
+                    bail!("all path dependencies must have a version \
+                           specified when packaging.\ndependency `{}` does \
+                           not specify a version", name)
+                }
+                path_only.push(name.clone());
+            }
+            if let Some(toml::Value::String(registry)) = dep.remove("registry") {
+                let id = try!(SourceId::alt_registry(config, &registry));
+                dep.insert("registry-index".to_string(),
+                           toml::Value::String(id.url().to_string()));
+            }
+        }
+        for name in path_only.iter() {
+            deps.remove(name);
+        }
+        Ok(())
+    }
+}
+
 type TomlLibTarget = TomlTarget;
 type TomlBinTarget = TomlTarget;
 type TomlExampleTarget = TomlTarget;
@@ -207,6 +277,8 @@ pub struct DetailedTomlDependency {
     features: Option<Vec<String>>,
     optional: Option<bool>,
     default_features: Option<bool>,
+    registry: Option<String>,
+    registry_index: Option<String>,
 }
 
 #[derive(RustcDecodable)]
@@ -264,6 +336,14 @@ pub struct TomlProject {
     license: Option<String>,
     license_file: Option<String>,
     repository: Option<String>,
+    publish: Option<VecStringOrBool>,
+}
+
+/// The `publish` key, either a flag or a list of allowed registry names.
+#[derive(RustcDecodable)]
+enum VecStringOrBool {
+    VecString(Vec<String>),
+    Bool(bool),
 }
 
 pub struct TomlVersion {
@@ -542,13 +622,21 @@ impl TomlManifest {
             keywords: project.keywords.clone().unwrap_or(Vec::new()),
         };
         let profiles = build_profiles(&self.profile);
+        let publish = match project.publish {
+            Some(VecStringOrBool::VecString(ref registries)) => {
+                Some(registries.clone())
+            }
+            Some(VecStringOrBool::Bool(false)) => Some(Vec::new()),
+            Some(VecStringOrBool::Bool(true)) | None => None,
+        };
         let mut manifest = Manifest::new(summary,
                                          targets,
                                          exclude,
                                          include,
                                          project.links.clone(),
                                          metadata,
-                                         profiles);
+                                         profiles,
+                                         publish);
         if project.license_file.is_some() && project.license.is_some() {
             manifest.add_warning(format!("warning: only one of `license` or \
                                                    `license-file` is necessary"));
@@ -652,6 +740,16 @@ fn process_dependencies<F>(cx: &mut Context,
             .or_else(|| details.rev.clone().map(GitReference::Rev))
             .unwrap_or_else(|| GitReference::Branch("master".to_string()));
 
+        if details.git.is_some() && details.registry.is_some() {
+            bail!("dependency ({}) specification is ambiguous. Only one of \
+                   `git` or `registry` is allowed.", n)
+        }
+        if details.registry_index.is_some() &&
+           (details.git.is_some() || details.registry.is_some()) {
+            bail!("dependency ({}) specification is ambiguous. Only one of \
+                   `git`, `registry` or `registry-index` is allowed.", n)
+        }
+
         let new_source_id = match details.git {
             Some(ref git) => {
                 let loc = try!(git.to_url().map_err(|e| {
@@ -665,7 +763,20 @@ fn process_dependencies<F>(cx: &mut Context,
                     cx.source_id.clone()
                 })
             }
-        }.unwrap_or(try!(SourceId::for_central(cx.config)));
+        };
+        let new_source_id = match (new_source_id, details.registry,
+                                   details.registry_index) {
+            (Some(id), _, _) => id,
+            (None, Some(ref registry), _) => {
+                try!(SourceId::alt_registry(cx.config, registry))
+            }
+            // Written by `cargo package` in place of `registry`, so packages
+            // from other registries resolve without any local configuration.
+            (None, None, Some(ref index)) => {
+                SourceId::for_registry(&try!(index.to_url().map_err(human)))
+            }
+            (None, None, None) => try!(SourceId::for_central(cx.config)),
+        };
 
         let dep = try!(DependencyInner::parse(&n,
                                               details.version.as_ref()
@@ -693,6 +804,7 @@ struct TomlTarget {
     doc: Option<bool>,
     plugin: Option<bool>,
     harness: Option<bool>,
+    required_features: Option<Vec<String>>,
 }
 
 #[derive(RustcDecodable, Clone)]
@@ -721,6 +833,7 @@ impl TomlTarget {
             doc: None,
             plugin: None,
             harness: None,
+            required_features: None,
         }
     }
 
@@ -765,7 +878,8 @@ fn normalize(lib: &Option<TomlLibTarget>,
               .set_doctest(toml.doctest.unwrap_or(t2.doctested()))
               .set_benched(toml.bench.unwrap_or(t2.benched()))
               .set_harness(toml.harness.unwrap_or(t2.harness()))
-              .set_for_host(toml.plugin.unwrap_or(t2.for_host()));
+              .set_for_host(toml.plugin.unwrap_or(t2.for_host()))
+              .set_required_features(toml.required_features.clone());
     }
 
     fn lib_target(dst: &mut Vec<Target>,
@@ -797,6 +911,11 @@ fn normalize(lib: &Option<TomlLibTarget>,
                                             &path.to_path(),
                                             metadata.clone());
         configure(l, &mut target);
+        if l.required_features.is_some() {
+            warnings.push("warning: required-features is ignored for the \
+                           [lib] section".to_string());
+            target.set_required_features(None);
+        }
         dst.push(target);
     }
 
@@ -927,6 +1046,14 @@ fn build_profiles(profiles: &Option<TomlProfiles>) -> Profiles {
         doc: merge(Profile::default_doc(),
                    profiles.and_then(|p| p.doc.as_ref())),
         custom_build: Profile::default_custom_build(),
+        check: merge(Profile::default_check(),
+                     profiles.and_then(|p| p.dev.as_ref())),
+        check_release: merge(Profile::default_check_release(),
+                             profiles.and_then(|p| p.release.as_ref())),
+        check_test: merge(Profile::default_check_test(),
+                          profiles.and_then(|p| p.test.as_ref())),
+        check_bench: merge(Profile::default_check_bench(),
+                           profiles.and_then(|p| p.bench.as_ref())),
     };
 
     fn merge(profile: Profile, toml: Option<&TomlProfile>) -> Profile {
@@ -948,6 +1075,7 @@ fn build_profiles(profiles: &Option<TomlProfiles>) -> Profiles {
             test: profile.test,
             doc: profile.doc,
             run_custom_build: profile.run_custom_build,
+            check: profile.check,
         }
     }
 }
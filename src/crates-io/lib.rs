@@ -2,6 +2,7 @@ extern crate curl;
 extern crate rustc_serialize;
 
 use std::collections::HashMap;
+use std::error;
 use std::fmt;
 use std::fs::{self, File};
 use std::io::prelude::*;
@@ -12,12 +13,13 @@ use std::result;
 use curl::http;
 use curl::http::handle::Method::{Put, Get, Delete};
 use curl::http::handle::{Method, Request};
-use rustc_serialize::json;
+use rustc_serialize::{json, Decodable, Decoder};
 
-pub struct Registry {
+pub struct Registry<'a> {
     host: String,
     token: Option<String>,
     handle: http::Handle,
+    retry: Option<Box<FnMut(&Error, u32) -> bool + 'a>>,
 }
 
 pub type Result<T> = result::Result<T, Error>;
@@ -37,13 +39,59 @@ pub enum Error {
     TokenMissing,
     Io(io::Error),
     NotFound,
+    Json(json::DecoderError),
 }
 
-#[derive(RustcDecodable)]
+#[derive(RustcDecodable, RustcEncodable)]
 pub struct Crate {
     pub name: String,
     pub description: Option<String>,
-    pub max_version: String
+    pub max_version: String,
+    pub downloads: Option<u64>,
+}
+
+pub struct CrateDetails {
+    pub krate: CrateInfo,
+    pub versions: Vec<Version>,
+}
+
+#[derive(RustcDecodable)]
+pub struct CrateInfo {
+    pub name: String,
+    pub description: Option<String>,
+    pub max_version: String,
+    pub downloads: Option<u64>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub homepage: Option<String>,
+    pub documentation: Option<String>,
+    pub repository: Option<String>,
+    pub keywords: Option<Vec<String>>,
+}
+
+#[derive(RustcDecodable)]
+pub struct Version {
+    pub id: u32,
+    pub num: String,
+    pub yanked: bool,
+    pub created_at: Option<String>,
+    pub downloads: Option<u64>,
+    pub license: Option<String>,
+}
+
+#[derive(RustcDecodable)]
+pub struct VersionDownloads {
+    pub version: u32,
+    pub downloads: u64,
+    pub date: String,
+}
+
+pub struct ReverseDependency {
+    pub name: String,
+    pub version: String,
+    pub req: String,
+    pub kind: String,
+    pub optional: bool,
 }
 
 #[derive(RustcEncodable)]
@@ -72,6 +120,7 @@ pub struct NewCrateDependency {
     pub version_req: String,
     pub target: Option<String>,
     pub kind: String,
+    pub registry: Option<String>,
 }
 
 #[derive(RustcDecodable)]
@@ -88,22 +137,79 @@ pub struct User {
 #[derive(RustcDecodable)] struct ApiError { detail: String }
 #[derive(RustcEncodable)] struct OwnersReq<'a> { users: &'a [&'a str] }
 #[derive(RustcDecodable)] struct Users { users: Vec<User> }
-#[derive(RustcDecodable)] struct Crates { crates: Vec<Crate> }
+#[derive(RustcDecodable)] struct Crates { crates: Vec<Crate>, meta: Option<Meta> }
+#[derive(RustcDecodable)] struct Versions { versions: Vec<Version> }
+#[derive(RustcDecodable)] struct Downloads { version_downloads: Vec<VersionDownloads> }
+#[derive(RustcDecodable)] struct Meta { total: u32 }
+#[derive(RustcDecodable)]
+struct ReverseDependencies {
+    dependencies: Vec<RawDependency>,
+    versions: Vec<DependentVersion>,
+    meta: Option<Meta>,
+}
+#[derive(RustcDecodable)]
+struct RawDependency {
+    version_id: u32,
+    req: String,
+    optional: bool,
+    kind: Option<String>,
+}
+struct DependentVersion { id: u32, krate: String, num: String }
+
+// The API names these fields `crate`, which can't be used as an identifier,
+// so they're decoded by hand.
+impl Decodable for CrateDetails {
+    fn decode<D: Decoder>(d: &mut D) -> result::Result<CrateDetails, D::Error> {
+        d.read_struct("CrateDetails", 2, |d| {
+            Ok(CrateDetails {
+                krate: try!(d.read_struct_field("crate", 0, Decodable::decode)),
+                versions: try!(d.read_struct_field("versions", 1,
+                                                   Decodable::decode)),
+            })
+        })
+    }
+}
 
-impl Registry {
-    pub fn new(host: String, token: Option<String>) -> Registry {
+impl Decodable for DependentVersion {
+    fn decode<D: Decoder>(d: &mut D)
+                          -> result::Result<DependentVersion, D::Error> {
+        d.read_struct("DependentVersion", 3, |d| {
+            Ok(DependentVersion {
+                id: try!(d.read_struct_field("id", 0, Decodable::decode)),
+                krate: try!(d.read_struct_field("crate", 1, Decodable::decode)),
+                num: try!(d.read_struct_field("num", 2, Decodable::decode)),
+            })
+        })
+    }
+}
+
+impl<'a> Registry<'a> {
+    pub fn new(host: String, token: Option<String>) -> Registry<'a> {
         Registry::new_handle(host, token, http::Handle::new())
     }
 
     pub fn new_handle(host: String, token: Option<String>,
-                      handle: http::Handle) -> Registry {
+                      handle: http::Handle) -> Registry<'a> {
         Registry {
             host: host,
             token: token,
             handle: handle,
+            retry: None,
         }
     }
 
+    /// Sets the policy for retrying failed requests. `retry` is called with
+    /// the error and the number of the attempt which failed, counting from 1,
+    /// and returns whether to try again, after waiting as long as it likes.
+    ///
+    /// Only `GET` requests are ever retried, as the others change the
+    /// registry and may have taken effect despite the error.
+    pub fn set_retry<F>(&mut self, retry: F)
+        where F: FnMut(&Error, u32) -> bool + 'a
+    {
+        self.retry = Some(Box::new(retry));
+    }
+
     pub fn add_owners(&mut self, krate: &str, owners: &[&str]) -> Result<()> {
         let body = json::encode(&OwnersReq { users: owners }).unwrap();
         let body = try!(self.put(format!("/crates/{}/owners", krate),
@@ -170,11 +276,69 @@ impl Registry {
         Ok(())
     }
 
-    pub fn search(&mut self, query: &str) -> Result<Vec<Crate>> {
-        let body = try!(self.req(format!("/crates?q={}", query), None, Get,
+    /// Fetches one page of search results, along with the total number of
+    /// crates matching `query`. Pages are numbered from 1.
+    ///
+    /// `sort` is passed through to the API, e.g. `downloads` or
+    /// `recent-updates`; with `None` results are ordered by relevance.
+    pub fn search(&mut self, query: &str, page: u32, per_page: u32,
+                  sort: Option<&str>) -> Result<(Vec<Crate>, u32)> {
+        let mut path = format!("/crates?q={}&page={}&per_page={}", query, page,
+                               per_page);
+        if let Some(sort) = sort {
+            path.push_str(&format!("&sort={}", sort));
+        }
+        let body = try!(self.req(path, None, Get, Auth::Unauthorized));
+
+        let crates = try!(decode::<Crates>(&body));
+        // Registries which don't report a total only have what was returned.
+        let total = crates.meta.map(|m| m.total)
+                          .unwrap_or(crates.crates.len() as u32);
+        Ok((crates.crates, total))
+    }
+
+    pub fn crate_info(&mut self, krate: &str) -> Result<CrateDetails> {
+        let body = try!(self.req(format!("/crates/{}", krate), None, Get,
                                  Auth::Unauthorized));
+        decode::<CrateDetails>(&body)
+    }
+
+    pub fn versions(&mut self, krate: &str) -> Result<Vec<Version>> {
+        let body = try!(self.req(format!("/crates/{}/versions", krate), None,
+                                 Get, Auth::Unauthorized));
+        Ok(try!(decode::<Versions>(&body)).versions)
+    }
 
-        Ok(json::decode::<Crates>(&body).unwrap().crates)
+    /// Daily download counts of each version over the last 90 days.
+    pub fn downloads(&mut self, krate: &str) -> Result<Vec<VersionDownloads>> {
+        let body = try!(self.req(format!("/crates/{}/downloads", krate), None,
+                                 Get, Auth::Unauthorized));
+        Ok(try!(decode::<Downloads>(&body)).version_downloads)
+    }
+
+    /// Returns the first page of crates depending on `krate`, along with the
+    /// total number of them.
+    pub fn reverse_dependencies(&mut self, krate: &str)
+                                -> Result<(Vec<ReverseDependency>, u32)> {
+        let body = try!(self.req(format!("/crates/{}/reverse_dependencies",
+                                         krate),
+                                 None, Get, Auth::Unauthorized));
+        let rdeps = try!(decode::<ReverseDependencies>(&body));
+        let total = rdeps.meta.map(|m| m.total)
+                         .unwrap_or(rdeps.dependencies.len() as u32);
+        let versions = rdeps.versions;
+        let deps = rdeps.dependencies.into_iter().filter_map(|dep| {
+            versions.iter().find(|v| v.id == dep.version_id).map(|v| {
+                ReverseDependency {
+                    name: v.krate.clone(),
+                    version: v.num.clone(),
+                    req: dep.req,
+                    kind: dep.kind.unwrap_or("normal".to_string()),
+                    optional: dep.optional,
+                }
+            })
+        }).collect();
+        Ok((deps, total))
     }
 
     pub fn yank(&mut self, krate: &str, version: &str) -> Result<()> {
@@ -205,6 +369,24 @@ impl Registry {
 
     fn req(&mut self, path: String, body: Option<&[u8]>,
            method: Method, authorized: Auth) -> Result<String> {
+        let idempotent = match method { Get => true, _ => false };
+        let mut attempt = 1;
+        loop {
+            let result = self.req_once(&path, body, method, authorized);
+            let retry = match result {
+                Err(ref e) if idempotent => match self.retry {
+                    Some(ref mut retry) => retry(e, attempt),
+                    None => false,
+                },
+                _ => false,
+            };
+            if !retry { return result }
+            attempt += 1;
+        }
+    }
+
+    fn req_once(&mut self, path: &str, body: Option<&[u8]>,
+                method: Method, authorized: Auth) -> Result<String> {
         let mut req = Request::new(&mut self.handle, method)
                               .uri(format!("{}/api/v1{}", self.host, path))
                               .header("Accept", "application/json")
@@ -250,6 +432,10 @@ fn handle(response: result::Result<http::Response, curl::ErrCode>)
     Ok(body)
 }
 
+fn decode<T: Decodable>(body: &str) -> Result<T> {
+    json::decode(body).map_err(Error::Json)
+}
+
 impl fmt::Display for Error {
     #[allow(deprecated)] // connect => join in 1.3
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -266,6 +452,31 @@ impl fmt::Display for Error {
             Error::TokenMissing => write!(f, "no upload token found, please run `cargo login`"),
             Error::Io(ref e) => write!(f, "io error: {}", e),
             Error::NotFound => write!(f, "cannot find crate"),
+            Error::Json(ref e) => {
+                write!(f, "failed to decode the registry's response: {}", e)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::NonUtf8Body => "response body was not utf-8",
+            Error::Curl(..) => "http error",
+            Error::NotOkResponse(..) => "failed to get a 200 OK response",
+            Error::Api(..) => "api errors",
+            Error::Unauthorized => "unauthorized API access",
+            Error::TokenMissing => "no upload token found",
+            Error::Io(..) => "io error",
+            Error::NotFound => "cannot find crate",
+            Error::Json(..) => "failed to decode the registry's response",
         }
     }
 }
@@ -22,33 +22,39 @@ _cargo()
 	local opt_jobs='-j --jobs'
 
 	local opt___nocmd="$opt_common -V --version --list"
-	local opt__bench="$opt_common $opt_pkg $opt_feat $opt_mani $opt_jobs --target --lib --bin --test --bench --example --no-run"
-	local opt__build="$opt_common $opt_pkg $opt_feat $opt_mani $opt_jobs --target --lib --bin --test --bench --example --release"
+	local opt__bench="$opt_common $opt_pkg $opt_feat $opt_mani $opt_jobs --target --lib --bin --test --bench --example --bins --examples --tests --benches --all-targets --no-run"
+	local opt__build="$opt_common $opt_pkg $opt_feat $opt_mani $opt_jobs --target --lib --bin --test --bench --example --bins --examples --tests --benches --all-targets --release"
+	local opt__check="$opt_common $opt_pkg $opt_feat $opt_mani $opt_jobs --target --lib --bin --test --bench --example --bins --examples --tests --benches --all-targets --release"
 	local opt__clean="$opt_common $opt_pkg $opt_mani --target --release"
-	local opt__doc="$opt_common $opt_pkg $opt_feat $opt_mani $opt_jobs --target --open --no-deps --release"
+	local opt__config="$opt_common --global get set list"
+	local opt__doc="$opt_common $opt_pkg $opt_feat $opt_mani $opt_jobs --target --open --no-deps --document-private-items --release --bins --examples --tests --benches --all-targets"
 	local opt__fetch="$opt_common $opt_mani"
 	local opt__generate_lockfile="${opt__fetch}"
 	local opt__git_checkout="$opt_common --reference --url"
+	local opt__git_gc="$opt_common --max-age -n --dry-run"
 	local opt__help="$opt_help"
+	local opt__info="$opt_common --host --registry --reverse-deps"
+	local opt__init="$opt_common --vcs --bin --name --template"
 	local opt__install="$opt_common $opt_feat $opt_jobs --bin --branch --debug --example --git --list --path --rev --root --tag --vers"
 	local opt__locate_project="$opt_mani -h --help"
-	local opt__login="$opt_common --host"
-	local opt__new="$opt_common --vcs --bin --name"
-	local opt__owner="$opt_common -a --add -r --remove -l --list --index --token"
+	local opt__login="$opt_common --host --registry"
+	local opt__logout="$opt_common --registry"
+	local opt__new="$opt_common --vcs --bin --name --template"
+	local opt__owner="$opt_common -a --add -r --remove -l --list --index --registry --token"
 	local opt__package="$opt_common $opt_mani -l --list --no-verify --no-metadata"
 	local opt__pkgid="${opt__fetch}"
-	local opt__publish="$opt_common $opt_mani --host --token --no-verify"
+	local opt__publish="$opt_common $opt_mani --host --registry --token --no-verify --dry-run"
 	local opt__read_manifest="$opt_help $opt_verbose $opt_mani --color"
 	local opt__run="$opt_common $opt_feat $opt_mani $opt_jobs --target --bin --example --release"
-	local opt__rustc="$opt_common $opt_pkg $opt_feat $opt_mani $opt_jobs --target --lib --bin --test --bench --example --release"
+	local opt__rustc="$opt_common $opt_pkg $opt_feat $opt_mani $opt_jobs --target --lib --bin --test --bench --example --bins --examples --tests --benches --all-targets --release"
 	local opt__rustdoc="$opt_common $opt_pkg $opt_feat $opt_mani $opt_jobs --target --lib --bin --test --bench --example --release --open"
-	local opt__search="$opt_common --host"
-	local opt__test="$opt_common $opt_pkg $opt_feat $opt_mani $opt_jobs --target --lib --bin --test --bench --example --no-run --release --no-fail-fast"
+	local opt__search="$opt_common --host --registry --limit --sort --json"
+	local opt__test="$opt_common $opt_pkg $opt_feat $opt_mani $opt_jobs --target --lib --bin --test --bench --example --bins --examples --tests --benches --all-targets --no-run --release --no-fail-fast --list --exact --test-jobs --report"
 	local opt__uninstall="$opt_common --bin --root"
 	local opt__update="$opt_common $opt_pkg $opt_mani --aggressive --precise"
 	local opt__verify_project="${opt__fetch}"
 	local opt__version="$opt_help $opt_verbose --color"
-	local opt__yank="$opt_common --vers --undo --index --token"
+	local opt__yank="$opt_common --vers --undo --index --registry --token"
 
 	if [[ $cword -eq 1 ]]; then
 		if [[ "$cur" == -* ]]; then
@@ -89,7 +95,7 @@ _cargo()
 } &&
 complete -F _cargo cargo
 
-__cargo_commands=$(cargo --list 2>/dev/null | tail -n +2)
+__cargo_commands=$(cargo --list 2>/dev/null | tail -n +2 | awk '{print $1}')
 
 _locate_manifest(){
 	local manifest=`cargo locate-project 2>/dev/null`
@@ -273,7 +273,8 @@ pub struct Execs {
     expect_stdin: Option<String>,
     expect_stderr: Option<String>,
     expect_exit_code: Option<i32>,
-    expect_stdout_contains: Vec<String>
+    expect_stdout_contains: Vec<String>,
+    expect_stderr_contains: Vec<String>
 }
 
 impl Execs {
@@ -298,6 +299,11 @@ impl Execs {
         self
     }
 
+    pub fn with_stderr_contains<S: ToString>(mut self, expected: S) -> Execs {
+        self.expect_stderr_contains.push(expected.to_string());
+        self
+    }
+
     fn match_output(&self, actual: &Output) -> ham::MatchResult {
         self.match_status(actual)
             .and(self.match_stdout(actual))
@@ -329,8 +335,13 @@ impl Execs {
     }
 
     fn match_stderr(&self, actual: &Output) -> ham::MatchResult {
-        self.match_std(self.expect_stderr.as_ref(), &actual.stderr,
-                       "stderr", &actual.stdout, false)
+        try!(self.match_std(self.expect_stderr.as_ref(), &actual.stderr,
+                            "stderr", &actual.stdout, false));
+        for expect in self.expect_stderr_contains.iter() {
+            try!(self.match_std(Some(expect), &actual.stderr, "stderr",
+                                &actual.stdout, true));
+        }
+        Ok(())
     }
 
     #[allow(deprecated)] // connect => join in 1.3
@@ -479,7 +490,8 @@ pub fn execs() -> Execs {
         expect_stderr: None,
         expect_stdin: None,
         expect_exit_code: None,
-        expect_stdout_contains: vec![]
+        expect_stdout_contains: vec![],
+        expect_stderr_contains: vec![]
     }
 }
 
@@ -17,6 +17,10 @@ pub fn registry_path() -> PathBuf { paths::root().join("registry") }
 pub fn registry() -> Url { Url::from_file_path(&*registry_path()).ok().unwrap() }
 pub fn dl_path() -> PathBuf { paths::root().join("dl") }
 pub fn dl_url() -> Url { Url::from_file_path(&*dl_path()).ok().unwrap() }
+pub fn alt_registry_path() -> PathBuf { paths::root().join("alternative-registry") }
+pub fn alt_registry() -> Url { Url::from_file_path(&*alt_registry_path()).ok().unwrap() }
+pub fn alt_dl_path() -> PathBuf { paths::root().join("alt_dl") }
+pub fn alt_dl_url() -> Url { Url::from_file_path(&*alt_dl_path()).ok().unwrap() }
 
 pub struct Package {
     name: String,
@@ -24,6 +28,7 @@ pub struct Package {
     deps: Vec<(String, String, &'static str)>,
     files: Vec<(String, String)>,
     yanked: bool,
+    alternative: bool,
 }
 
 fn init() {
@@ -36,7 +41,10 @@ fn init() {
         [registry]
             index = "{reg}"
             token = "api-token"
-    "#, reg = registry()).as_bytes()).unwrap();
+
+        [registries.alternative]
+            index = "{alt}"
+    "#, reg = registry(), alt = alt_registry()).as_bytes()).unwrap();
 
     // Init a new registry
     repo(&registry_path())
@@ -44,6 +52,13 @@ fn init() {
             {{"dl":"{}","api":""}}
         "#, dl_url()))
         .build();
+
+    // Init an alternative registry
+    repo(&alt_registry_path())
+        .file("config.json", &format!(r#"
+            {{"dl":"{}","api":""}}
+        "#, alt_dl_url()))
+        .build();
 }
 
 impl Package {
@@ -55,6 +70,7 @@ impl Package {
             deps: Vec::new(),
             files: Vec::new(),
             yanked: false,
+            alternative: false,
         }
     }
 
@@ -78,6 +94,13 @@ impl Package {
         self
     }
 
+    /// Publish this package to the `alternative` named registry instead of
+    /// the default one.
+    pub fn alternative(&mut self, alternative: bool) -> &mut Package {
+        self.alternative = alternative;
+        self
+    }
+
     #[allow(deprecated)] // connect => join in 1.3
     pub fn publish(&self) {
         self.make_archive();
@@ -110,7 +133,12 @@ impl Package {
         };
 
         // Write file/line in the index
-        let dst = registry_path().join(&file);
+        let registry_path = if self.alternative {
+            alt_registry_path()
+        } else {
+            registry_path()
+        };
+        let dst = registry_path.join(&file);
         let mut prev = String::new();
         let _ = File::open(&dst).and_then(|mut f| f.read_to_string(&mut prev));
         fs::create_dir_all(dst.parent().unwrap()).unwrap();
@@ -118,7 +146,7 @@ impl Package {
              .write_all((prev + &line[..] + "\n").as_bytes()).unwrap();
 
         // Add the new file to the index
-        let repo = git2::Repository::open(&registry_path()).unwrap();
+        let repo = git2::Repository::open(&registry_path).unwrap();
         let mut index = repo.index().unwrap();
         index.add_path(Path::new(&file)).unwrap();
         index.write().unwrap();
@@ -177,7 +205,8 @@ impl Package {
     }
 
     pub fn archive_dst(&self) -> PathBuf {
-        dl_path().join(&self.name).join(&self.vers).join("download")
+        let dl_path = if self.alternative {alt_dl_path()} else {dl_path()};
+        dl_path.join(&self.name).join(&self.vers).join("download")
     }
 }
 
@@ -126,3 +126,42 @@ test!(cargo_help {
                        .arg("help").arg("help"),
                 execs().with_status(0));
 });
+
+test!(subcommand_gets_cargo_environment {
+    let p = project("cargo-envtest")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "cargo-envtest"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/main.rs", r#"
+            use std::env;
+
+            fn main() {
+                for var in ["CARGO", "CARGO_HOME", "CARGO_TERM_VERBOSE",
+                            "CARGO_TERM_QUIET", "CARGO_TERM_COLOR"].iter() {
+                    println!("{}={}", var, env::var(var).unwrap());
+                }
+            }
+        "#);
+    assert_that(p.cargo_process("build"), execs().with_status(0));
+
+    let mut path = path();
+    path.push(p.root().join("target/debug"));
+    let path = env::join_paths(path.iter()).unwrap();
+
+    let mut pr = process(&cargo_dir().join("cargo"));
+    pr.arg("-v").arg("--color").arg("never").arg("envtest")
+      .cwd(&p.root())
+      .env("HOME", &paths::home())
+      .env_remove("CARGO_HOME")
+      .env("PATH", &path);
+    assert_that(pr, execs().with_status(0).with_stdout(&format!("\
+CARGO=[..]cargo[..]
+CARGO_HOME={home}
+CARGO_TERM_VERBOSE=true
+CARGO_TERM_QUIET=false
+CARGO_TERM_COLOR=never
+", home = paths::home().join(".cargo").display())));
+});
@@ -0,0 +1,154 @@
+use support::{project, execs, basic_bin_manifest, main_file};
+use support::COMPILING;
+use hamcrest::{assert_that, existing_file};
+
+fn setup() {
+}
+
+test!(alias_incorrect_config_type {
+    let p = project("foo")
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", &main_file(r#""i am foo""#, &[]))
+        .file(".cargo/config", r#"
+            [alias]
+            b-cargo-test = 5
+        "#);
+
+    assert_that(p.cargo_process("b-cargo-test").arg("-v"),
+                execs().with_status(101).with_stderr("\
+invalid configuration for key `alias.b-cargo-test`
+expected a string or list, but found a integer in [..]config
+"));
+});
+
+test!(alias_string {
+    let p = project("foo")
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", &main_file(r#""i am foo""#, &[]))
+        .file(".cargo/config", r#"
+            [alias]
+            b-cargo-test = "build --release"
+        "#);
+
+    assert_that(p.cargo_process("b-cargo-test"),
+                execs().with_status(0)
+                       .with_stdout(format!("{} foo v0.5.0 ([..])",
+                                            COMPILING)));
+    assert_that(&p.root().join("target/release/foo"), existing_file());
+});
+
+test!(alias_list {
+    let p = project("foo")
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", &main_file(r#""i am foo""#, &[]))
+        .file(".cargo/config", r#"
+            [alias]
+            b-cargo-test = ["build", "--release"]
+        "#);
+
+    assert_that(p.cargo_process("b-cargo-test"), execs().with_status(0));
+    assert_that(&p.root().join("target/release/foo"), existing_file());
+});
+
+test!(alias_from_environment {
+    let p = project("foo")
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", &main_file(r#""i am foo""#, &[]));
+
+    assert_that(p.cargo_process("b-cargo-test")
+                 .env("CARGO_ALIAS_B_CARGO_TEST", "build --release"),
+                execs().with_status(0));
+    assert_that(&p.root().join("target/release/foo"), existing_file());
+});
+
+test!(alias_passes_extra_args {
+    let p = project("foo")
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", &main_file(r#""i am foo""#, &[]))
+        .file(".cargo/config", r#"
+            [alias]
+            b-cargo-test = "build"
+        "#);
+
+    assert_that(p.cargo_process("b-cargo-test").arg("--release"),
+                execs().with_status(0));
+    assert_that(&p.root().join("target/release/foo"), existing_file());
+});
+
+test!(alias_of_alias {
+    let p = project("foo")
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", &main_file(r#""i am foo""#, &[]))
+        .file(".cargo/config", r#"
+            [alias]
+            b-cargo-test = "b-release"
+            b-release = "build --release"
+        "#);
+
+    assert_that(p.cargo_process("b-cargo-test"), execs().with_status(0));
+    assert_that(&p.root().join("target/release/foo"), existing_file());
+});
+
+test!(alias_cycle {
+    let p = project("foo")
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", &main_file(r#""i am foo""#, &[]))
+        .file(".cargo/config", r#"
+            [alias]
+            a = "b --release"
+            b = "a"
+        "#);
+
+    assert_that(p.cargo_process("a"),
+                execs().with_status(101).with_stderr("\
+alias `a` is defined in terms of itself: a -> b -> a
+"));
+});
+
+test!(alias_cannot_shadow_builtin_command {
+    let p = project("foo")
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", &main_file(r#""i am foo""#, &[]))
+        .file(".cargo/config", r#"
+            [alias]
+            build = "fetch"
+        "#);
+
+    assert_that(p.cargo_process("build"),
+                execs().with_status(101).with_stderr("\
+alias `build` shadows the built-in `cargo build` command, remove it from the \
+`[alias]` table
+"));
+});
+
+test!(alias_shown_in_list {
+    let p = project("foo")
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", &main_file(r#""i am foo""#, &[]))
+        .file(".cargo/config", r#"
+            [alias]
+            b-cargo-test = "build --release"
+        "#);
+
+    assert_that(p.cargo_process("--list"),
+                execs().with_status(0)
+                       .with_stdout_contains("    b-cargo-test         \
+                                              alias: build --release"));
+});
+
+test!(alias_suggested_for_typo {
+    let p = project("foo")
+        .file("Cargo.toml", &basic_bin_manifest("foo"))
+        .file("src/main.rs", &main_file(r#""i am foo""#, &[]))
+        .file(".cargo/config", r#"
+            [alias]
+            xyzzy = "build --release"
+        "#);
+
+    assert_that(p.cargo_process("xyzy"),
+                execs().with_status(127).with_stderr("No such subcommand
+
+Did you mean `xyzzy`?
+
+"));
+});
@@ -0,0 +1,182 @@
+use std::path::MAIN_SEPARATOR as SEP;
+
+use support::{execs, project, path2url};
+use support::{COMPILING, RUNNING};
+use hamcrest::{assert_that, existing_file, is_not};
+
+fn setup() {
+}
+
+test!(check_success {
+    let foo = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [dependencies.bar]
+            path = "../bar"
+        "#)
+        .file("src/main.rs", r#"
+            extern crate bar;
+            fn main() {
+                ::bar::baz();
+            }
+        "#);
+    let bar = project("bar")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "bar"
+            version = "0.1.0"
+            authors = []
+        "#)
+        .file("src/lib.rs", r#"
+            pub fn baz() {}
+        "#);
+    bar.build();
+
+    assert_that(foo.cargo_process("check"),
+                execs().with_status(0));
+    assert_that(&foo.bin("foo"), is_not(existing_file()));
+});
+
+test!(check_fail {
+    let foo = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/main.rs", r#"
+            fn main() {
+                let x: u32 = "not a number";
+            }
+        "#);
+
+    assert_that(foo.cargo_process("check"),
+                execs().with_status(101));
+});
+
+test!(check_emits_metadata_only {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "");
+
+    assert_that(p.cargo_process("check").arg("-v"),
+                execs()
+                .with_status(0)
+                .with_stdout(format!("\
+{compiling} foo v0.0.1 ({url})
+{running} `rustc src{sep}lib.rs --crate-name foo --crate-type lib -g \
+        --out-dir {dir}{sep}target{sep}debug \
+        --emit=dep-info,metadata \
+        -L dependency={dir}{sep}target{sep}debug \
+        -L dependency={dir}{sep}target{sep}debug{sep}deps`
+",
+            running = RUNNING, compiling = COMPILING, sep = SEP,
+            dir = p.root().display(), url = p.url())));
+    assert_that(&p.root().join("target/debug/libfoo.rmeta"), existing_file());
+    assert_that(&p.root().join("target/debug/libfoo.rlib"),
+                is_not(existing_file()));
+});
+
+test!(check_and_build_do_not_invalidate_each_other {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("check"),
+                execs().with_status(0).with_stdout(format!("\
+{compiling} foo v0.0.1 ({dir})
+", compiling = COMPILING, dir = path2url(p.root()))));
+    assert_that(p.cargo("build"),
+                execs().with_status(0).with_stdout(format!("\
+{compiling} foo v0.0.1 ({dir})
+", compiling = COMPILING, dir = path2url(p.root()))));
+    assert_that(p.cargo("check"),
+                execs().with_status(0).with_stdout(""));
+    assert_that(p.cargo("build"),
+                execs().with_status(0).with_stdout(""));
+    assert_that(&p.bin("foo"), existing_file());
+});
+
+test!(check_release {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "");
+
+    assert_that(p.cargo_process("check").arg("--release").arg("-v"),
+                execs().with_status(0).with_stdout_contains(format!("\
+{running} `rustc src{sep}lib.rs --crate-name foo --crate-type lib \
+        -C opt-level=3 \
+        --out-dir {dir}{sep}target{sep}release \
+        --emit=dep-info,metadata [..]`
+", running = RUNNING, sep = SEP, dir = p.root().display())));
+    assert_that(&p.root().join("target/release/libfoo.rmeta"), existing_file());
+});
+
+test!(check_tests_skips_codegen {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+        .file("tests/a.rs", "#[test] fn a() {}")
+        .file("benches/b.rs", "");
+
+    assert_that(p.cargo_process("check").arg("--tests").arg("--benches")
+                 .arg("-v"),
+                execs().with_status(0)
+                       .with_stdout_contains("\
+[..]`rustc tests[..]a.rs --crate-name a --test -g [..]\
+--emit=dep-info,metadata [..]`")
+                       .with_stdout_contains("\
+[..]`rustc benches[..]b.rs --crate-name b --test -C opt-level=3 [..]\
+--emit=dep-info,metadata [..]`"));
+});
+
+test!(check_lib_and_bin_with_same_name {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "pub fn foo() {}")
+        .file("src/main.rs", r#"
+            extern crate foo;
+            fn main() { foo::foo(); }
+        "#);
+
+    assert_that(p.cargo_process("check"),
+                execs().with_status(0).with_stdout(format!("\
+{compiling} foo v0.0.1 ({dir})
+", compiling = COMPILING, dir = path2url(p.root()))));
+    assert_that(&p.root().join("target/debug/libfoo.rmeta"), existing_file());
+
+    // The binary's metadata must not have replaced the library's, otherwise
+    // the second check would rebuild both.
+    assert_that(p.cargo("check"),
+                execs().with_status(0).with_stdout(""));
+});
@@ -2052,3 +2052,47 @@ test!(manifest_with_bom_is_ok {
     assert_that(p.cargo_process("build").arg("-v"),
                 execs().with_status(0));
 });
+
+test!(build_all_examples {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+        .file("src/main.rs", "fn main() {}")
+        .file("examples/a.rs", "fn main() {}")
+        .file("examples/b.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("build").arg("--examples"),
+                execs().with_status(0));
+    assert_that(&p.bin("examples/a"), existing_file());
+    assert_that(&p.bin("examples/b"), existing_file());
+    assert_that(&p.bin("foo"), is_not(existing_file()));
+});
+
+test!(build_all_targets {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/main.rs", "fn main() {}")
+        .file("examples/a.rs", "fn main() {}")
+        .file("tests/t.rs", "")
+        .file("benches/b.rs", "");
+
+    assert_that(p.cargo_process("build").arg("--all-targets").arg("-v"),
+                execs().with_status(0)
+                       .with_stdout_contains("[..]--crate-name foo \
+                                              --crate-type bin[..]")
+                       .with_stdout_contains("[..]--crate-name a[..]")
+                       .with_stdout_contains("[..]--crate-name t[..]--test[..]")
+                       .with_stdout_contains("[..]--crate-name b[..]--test[..]"));
+    assert_that(&p.bin("foo"), existing_file());
+    assert_that(&p.bin("examples/a"), existing_file());
+});
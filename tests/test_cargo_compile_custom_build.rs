@@ -1707,3 +1707,225 @@ test!(changing_an_override_invalidates {
 {running} `rustc [..] -L native=bar`
 ", compiling = COMPILING, running = RUNNING)));
 });
+
+test!(rerun_if_changed_is_only_input {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.5.0"
+            authors = []
+            build = "build.rs"
+        "#)
+        .file("src/lib.rs", "")
+        .file("foo.proto", "")
+        .file("build.rs", r#"
+            fn main() {
+                println!("cargo:rerun-if-changed=foo.proto");
+            }
+        "#);
+    assert_that(p.cargo_process("build").arg("-v"),
+                execs().with_status(0).with_stdout(&format!("\
+{compiling} foo v0.5.0 ([..]
+{running} `rustc build.rs [..]`
+{running} `[..]build-script-build[..]`
+{running} `rustc src[..]lib.rs [..]`
+", compiling = COMPILING, running = RUNNING)));
+    p.root().move_into_the_past().unwrap();
+
+    // Editing an unrelated source file only rebuilds the library
+    File::create(&p.root().join("src/lib.rs")).unwrap()
+         .write_all(b"pub fn foo() {}").unwrap();
+    assert_that(p.cargo("build").arg("-v"),
+                execs().with_status(0).with_stdout(&format!("\
+{compiling} foo v0.5.0 ([..]
+{running} `rustc src[..]lib.rs [..]`
+", compiling = COMPILING, running = RUNNING)));
+    p.root().move_into_the_past().unwrap();
+
+    // Editing the listed file reruns the build script
+    File::create(&p.root().join("foo.proto")).unwrap()
+         .write_all(b"message Foo {}").unwrap();
+    assert_that(p.cargo("build").arg("-v"),
+                execs().with_status(0).with_stdout(&format!("\
+{compiling} foo v0.5.0 ([..]
+{running} `[..]build-script-build[..]`
+{running} `rustc src[..]lib.rs [..]`
+", compiling = COMPILING, running = RUNNING)));
+});
+
+test!(rerun_if_changed_missing_file {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.5.0"
+            authors = []
+            build = "build.rs"
+        "#)
+        .file("src/lib.rs", "")
+        .file("build.rs", r#"
+            fn main() {
+                println!("cargo:rerun-if-changed=missing.txt");
+            }
+        "#);
+    assert_that(p.cargo_process("build").arg("-v"),
+                execs().with_status(0).with_stdout(&format!("\
+{compiling} foo v0.5.0 ([..]
+{running} `rustc build.rs [..]`
+{running} `[..]build-script-build[..]`
+{running} `rustc src[..]lib.rs [..]`
+", compiling = COMPILING, running = RUNNING)));
+
+    // A file which doesn't exist is always out of date, so the script runs
+    // every time.
+    assert_that(p.cargo("build").arg("-v"),
+                execs().with_status(0).with_stdout_contains(&format!("\
+{running} `[..]build-script-build[..]`
+", running = RUNNING)));
+    assert_that(p.cargo("build"), execs().with_status(0));
+});
+
+test!(rerun_if_env_changed {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.5.0"
+            authors = []
+            build = "build.rs"
+        "#)
+        .file("src/lib.rs", "")
+        .file("build.rs", r#"
+            fn main() {
+                println!("cargo:rerun-if-env-changed=FOO");
+            }
+        "#);
+    assert_that(p.cargo_process("build").env("FOO", "a"),
+                execs().with_status(0));
+    p.root().move_into_the_past().unwrap();
+
+    File::create(&p.root().join("src/lib.rs")).unwrap()
+         .write_all(b"pub fn foo() {}").unwrap();
+    assert_that(p.cargo("build").arg("-v").env("FOO", "a"),
+                execs().with_status(0).with_stdout(&format!("\
+{compiling} foo v0.5.0 ([..]
+{running} `rustc src[..]lib.rs [..]`
+", compiling = COMPILING, running = RUNNING)));
+
+    assert_that(p.cargo("build").arg("-v").env("FOO", "b"),
+                execs().with_status(0).with_stdout(&format!("\
+{compiling} foo v0.5.0 ([..]
+{running} `[..]build-script-build[..]`
+{running} `rustc src[..]lib.rs [..]`
+", compiling = COMPILING, running = RUNNING)));
+
+    assert_that(p.cargo("build").arg("-v").env("FOO", "b"),
+                execs().with_status(0).with_stdout(&format!("\
+{fresh} foo v0.5.0 ([..]
+", fresh = FRESH)));
+});
+
+test!(build_script_warnings_are_shown {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.5.0"
+            authors = []
+            build = "build.rs"
+        "#)
+        .file("src/lib.rs", "")
+        .file("build.rs", r#"
+            fn main() {
+                println!("cargo:warning=foo");
+                println!("cargo:warning=bar");
+            }
+        "#);
+
+    assert_that(p.cargo_process("build"),
+                execs().with_status(0).with_stderr("\
+warning: foo
+warning: bar
+"));
+
+    // A fresh build doesn't rerun the script, so nothing is printed
+    assert_that(p.cargo("build"),
+                execs().with_status(0).with_stderr(""));
+});
+
+test!(rustc_env_is_available_to_env_macro {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.5.0"
+            authors = []
+            build = "build.rs"
+        "#)
+        .file("src/main.rs", r#"
+            const FOO: &'static str = env!("FOO");
+            fn main() {
+                println!("{}", FOO);
+            }
+        "#)
+        .file("build.rs", r#"
+            fn main() {
+                println!("cargo:rustc-env=FOO=foo=bar");
+            }
+        "#);
+
+    assert_that(p.cargo_process("run"),
+                execs().with_status(0).with_stdout_contains("foo=bar"));
+});
+
+test!(rustc_env_without_value {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.5.0"
+            authors = []
+            build = "build.rs"
+        "#)
+        .file("src/lib.rs", "")
+        .file("build.rs", r#"
+            fn main() {
+                println!("cargo:rustc-env=FOO");
+            }
+        "#);
+
+    assert_that(p.cargo_process("build"),
+                execs().with_status(101)
+                       .with_stderr("\
+Variable rustc-env has no value in build script of `foo v0.5.0 ([..])`: FOO
+"));
+});
+
+test!(rustc_link_arg_only_for_binaries {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.5.0"
+            authors = []
+            build = "build.rs"
+        "#)
+        .file("src/lib.rs", "")
+        .file("src/main.rs", "fn main() {}")
+        .file("build.rs", r#"
+            fn main() {
+                println!("cargo:rustc-link-arg=-Wl,--as-needed");
+            }
+        "#);
+
+    assert_that(p.cargo_process("build").arg("-v"),
+                execs().with_status(0).with_stdout(&format!("\
+{compiling} foo v0.5.0 ([..]
+{running} `rustc build.rs [..]`
+{running} `[..]build-script-build[..]`
+{running} `rustc src[..]lib.rs --crate-name foo --crate-type lib [..]`
+{running} `rustc src[..]main.rs --crate-name foo --crate-type bin [..]\
+-C link-arg=-Wl,--as-needed[..]`
+", compiling = COMPILING, running = RUNNING)));
+});
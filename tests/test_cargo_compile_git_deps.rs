@@ -211,6 +211,170 @@ test!(cargo_compile_git_dep_tag {
                 execs().with_status(0));
 });
 
+test!(git_dep_tag_only_fetches_tag {
+    let git_project = git::new("dep1", |project| {
+        project.file("Cargo.toml", r#"
+            [project]
+            name = "dep1"
+            version = "0.5.0"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+    }).unwrap();
+
+    let repo = git2::Repository::open(&git_project.root()).unwrap();
+    let head = repo.head().unwrap().target().unwrap();
+    repo.tag("v0.1.0",
+             &repo.find_object(head, None).unwrap(),
+             &repo.signature().unwrap(),
+             "make a new tag",
+             false).unwrap();
+    repo.tag("v0.2.0",
+             &repo.find_object(head, None).unwrap(),
+             &repo.signature().unwrap(),
+             "make another tag",
+             false).unwrap();
+
+    let project = project("foo")
+        .file("Cargo.toml", &format!(r#"
+            [project]
+            name = "foo"
+            version = "0.5.0"
+            authors = []
+
+            [dependencies.dep1]
+            git = '{}'
+            tag = "v0.1.0"
+        "#, git_project.url()))
+        .file("src/lib.rs", "");
+
+    assert_that(project.cargo_process("build"), execs().with_status(0));
+
+    // Neither branches nor other tags should have been fetched
+    let db = paths::home().join(".cargo/git/db");
+    let db = fs::read_dir(&db).unwrap().next().unwrap().unwrap().path();
+    let db = git2::Repository::open(&db).unwrap();
+    let refs = db.references().unwrap().map(|r| {
+        r.unwrap().name().unwrap().to_string()
+    }).collect::<Vec<_>>();
+    assert_eq!(refs, vec!["refs/tags/v0.1.0".to_string()]);
+});
+
+test!(git_dep_pinned_rev_is_not_refetched {
+    let git_project = git::new("dep1", |project| {
+        project.file("Cargo.toml", r#"
+            [project]
+            name = "dep1"
+            version = "0.5.0"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+    }).unwrap();
+
+    let repo = git2::Repository::open(&git_project.root()).unwrap();
+    let rev = repo.revparse_single("HEAD").unwrap().id();
+
+    let project = project("foo")
+        .file("Cargo.toml", &format!(r#"
+            [project]
+            name = "foo"
+            version = "0.5.0"
+            authors = []
+
+            [dependencies.dep1]
+            git = '{}'
+            rev = "{}"
+        "#, git_project.url(), rev))
+        .file("src/lib.rs", "");
+
+    assert_that(project.cargo_process("build"),
+                execs().with_status(0)
+                       .with_stdout_contains(format!("{} git repository [..]",
+                                                     UPDATING)));
+
+    // Without a lock file the revision is resolved again, but it's already in
+    // the database so the network isn't touched.
+    fs::remove_file(&project.root().join("Cargo.lock")).unwrap();
+    assert_that(project.cargo("build"),
+                execs().with_status(0).with_stdout(""));
+});
+
+test!(git_dep_rev_on_other_branch {
+    let git_project = git::new("dep1", |project| {
+        project.file("Cargo.toml", r#"
+            [project]
+            name = "dep1"
+            version = "0.5.0"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+    }).unwrap();
+
+    // Commit to a branch other than the default one
+    let repo = git2::Repository::open(&git_project.root()).unwrap();
+    let head = repo.head().unwrap().target().unwrap();
+    let parent = repo.find_commit(head).unwrap();
+    let sig = repo.signature().unwrap();
+    repo.branch("feature", &parent, false).unwrap();
+    let rev = repo.commit(Some("refs/heads/feature"), &sig, &sig, "feature",
+                          &parent.tree().unwrap(), &[&parent]).unwrap();
+
+    let project = project("foo")
+        .file("Cargo.toml", &format!(r#"
+            [project]
+            name = "foo"
+            version = "0.5.0"
+            authors = []
+
+            [dependencies.dep1]
+            git = '{}'
+            rev = "{}"
+        "#, git_project.url(), rev))
+        .file("src/lib.rs", "");
+
+    assert_that(project.cargo_process("build"), execs().with_status(0));
+});
+
+test!(git_dep_rev_only_on_tag {
+    let git_project = git::new("dep1", |project| {
+        project.file("Cargo.toml", r#"
+            [project]
+            name = "dep1"
+            version = "0.5.0"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+    }).unwrap();
+
+    // Commit without moving any branch, so only the tag reaches it
+    let repo = git2::Repository::open(&git_project.root()).unwrap();
+    let head = repo.head().unwrap().target().unwrap();
+    let parent = repo.find_commit(head).unwrap();
+    let sig = repo.signature().unwrap();
+    let rev = repo.commit(None, &sig, &sig, "tagged",
+                          &parent.tree().unwrap(), &[&parent]).unwrap();
+    repo.tag("v0.1.0",
+             &repo.find_object(rev, None).unwrap(),
+             &sig,
+             "tag a detached commit",
+             false).unwrap();
+
+    let project = project("foo")
+        .file("Cargo.toml", &format!(r#"
+            [project]
+            name = "foo"
+            version = "0.5.0"
+            authors = []
+
+            [dependencies.dep1]
+            git = '{}'
+            rev = "{}"
+        "#, git_project.url(), rev))
+        .file("src/lib.rs", "");
+
+    assert_that(project.cargo_process("build"), execs().with_status(0));
+});
+
 test!(cargo_compile_with_nested_paths {
     let git_project = git::new("dep1", |project| {
         project
@@ -0,0 +1,203 @@
+use std::fs::{self, File};
+use std::io::prelude::*;
+
+use support::{project, execs};
+use support::paths;
+use hamcrest::assert_that;
+
+fn setup() {
+}
+
+fn read(path: &::std::path::Path) -> String {
+    let mut contents = String::new();
+    File::open(path).unwrap().read_to_string(&mut contents).unwrap();
+    contents
+}
+
+test!(get_prints_value_and_definition {
+    let p = project("foo")
+        .file(".cargo/config", r#"
+            [build]
+            jobs = 2
+            target-dir = "out"
+        "#);
+
+    assert_that(p.cargo_process("config").arg("get").arg("build.jobs"),
+                execs().with_status(0).with_stdout(&format!("\
+build.jobs = 2  # {}
+", p.root().join(".cargo/config").display())));
+
+    assert_that(p.cargo("config").arg("get").arg("build"),
+                execs().with_status(0).with_stdout("\
+build.jobs = 2  # [..].cargo[..]config
+build.target-dir = \"out\"  # [..].cargo[..]config
+"));
+});
+
+test!(get_missing_key {
+    let p = project("foo");
+
+    assert_that(p.cargo_process("config").arg("get").arg("build.jobs"),
+                execs().with_status(101).with_stderr("\
+configuration key `build.jobs` is not set
+"));
+});
+
+test!(get_shows_environment_variable {
+    let p = project("foo")
+        .file(".cargo/config", r#"
+            [build]
+            jobs = 2
+        "#);
+
+    assert_that(p.cargo_process("config").arg("get").arg("build.jobs")
+                 .env("CARGO_BUILD_JOBS", "4"),
+                execs().with_status(0).with_stdout("\
+build.jobs = 4  # environment variable `CARGO_BUILD_JOBS`
+"));
+});
+
+test!(get_table_includes_environment_variables {
+    let p = project("foo")
+        .file(".cargo/config", r#"
+            [build]
+            jobs = 2
+        "#);
+
+    assert_that(p.cargo_process("config").arg("get").arg("build")
+                 .env("CARGO_BUILD_JOBS", "4")
+                 .env("CARGO_BUILD_TARGET_DIR", "out"),
+                execs().with_status(0).with_stdout("\
+build.jobs = 4  # environment variable `CARGO_BUILD_JOBS`
+build.target-dir = \"out\"  # environment variable `CARGO_BUILD_TARGET_DIR`
+"));
+
+    assert_that(p.cargo("config").arg("get").arg("alias")
+                 .env("CARGO_ALIAS_B", "build"),
+                execs().with_status(0).with_stdout("\
+alias.b = \"build\"  # environment variable `CARGO_ALIAS_B`
+"));
+});
+
+test!(list_merges_files {
+    let p = project("foo")
+        .file(".cargo/config", r#"
+            [build]
+            jobs = 2
+        "#);
+    fs::create_dir_all(paths::home().join(".cargo")).unwrap();
+    let mut f = File::create(paths::home().join(".cargo/config")).unwrap();
+    f.write_all(br#"
+        [build]
+        jobs = 8
+
+        [term]
+        verbose = true
+    "#).unwrap();
+
+    assert_that(p.cargo_process("config").arg("list"),
+                execs().with_status(0)
+                       .with_stdout_contains(&format!("\
+build.jobs = 2  # {}
+", p.root().join(".cargo/config").display()))
+                       .with_stdout_contains(&format!("\
+term.verbose = true  # {}
+", paths::home().join(".cargo/config").display())));
+});
+
+test!(list_redacts_tokens {
+    let p = project("foo");
+    fs::create_dir_all(paths::home().join(".cargo")).unwrap();
+
+    assert_that(p.cargo_process("login").arg("secret-token"),
+                execs().with_status(0));
+
+    assert_that(p.cargo("config").arg("list"),
+                execs().with_status(0).with_stdout_contains(&format!("\
+registry.token = \"<redacted>\"  # {}
+", paths::home().join(".cargo/credentials").display())));
+    let output = p.cargo("config").arg("list").exec_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("secret-token"), "{}", stdout);
+
+    assert_that(p.cargo("config").arg("get").arg("registry"),
+                execs().with_status(0).with_stdout("\
+registry.token = \"<redacted>\"  # [..]credentials
+"));
+});
+
+test!(set_project_keeps_comments {
+    let p = project("foo")
+        .file(".cargo/config", "\
+# shared settings
+[build]
+jobs = 2 # for CI
+
+[net]
+retry = 3
+");
+
+    assert_that(p.cargo_process("config").arg("set")
+                 .arg("build.jobs").arg("4"),
+                execs().with_status(0).with_stderr(&format!("\
+     Updated `build.jobs` in {}
+", p.root().join(".cargo/config").display())));
+    assert_eq!(read(&p.root().join(".cargo/config")), "\
+# shared settings
+[build]
+jobs = 4 # for CI
+
+[net]
+retry = 3
+");
+
+    assert_that(p.cargo("config").arg("set")
+                 .arg("build.target-dir").arg("out dir"),
+                execs().with_status(0));
+    assert_that(p.cargo("config").arg("set")
+                 .arg("http.proxy").arg("http://localhost:3128"),
+                execs().with_status(0));
+    assert_eq!(read(&p.root().join(".cargo/config")), "\
+# shared settings
+[build]
+jobs = 4 # for CI
+target-dir = \"out dir\"
+
+[net]
+retry = 3
+
+[http]
+proxy = \"http://localhost:3128\"
+");
+});
+
+test!(set_global {
+    let p = project("foo");
+
+    assert_that(p.cargo_process("config").arg("set").arg("--global")
+                 .arg("term.verbose").arg("true"),
+                execs().with_status(0).with_stderr(&format!("\
+     Updated `term.verbose` in {}
+", paths::home().join(".cargo/config").display())));
+    assert_eq!(read(&paths::home().join(".cargo/config")), "\
+[term]
+verbose = true
+");
+});
+
+test!(set_rejects_what_it_cannot_edit {
+    let p = project("foo")
+        .file(".cargo/config", r#"
+            [build]
+            rustflags = [
+                "-C", "opt-level=1",
+            ]
+        "#);
+
+    assert_that(p.cargo_process("config").arg("set")
+                 .arg("build.rustflags").arg("[]"),
+                execs().with_status(101).with_stderr("\
+could not update `build.rustflags` in [..] without disturbing the rest of \
+the file, it needs to be edited by hand
+"));
+});
@@ -1,3 +1,6 @@
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
 use std::str;
 
 use support::{project, execs, path2url};
@@ -204,11 +207,16 @@ test!(doc_lib_bin_same_name {
         .file("src/lib.rs", "fn foo() {}");
 
     assert_that(p.cargo_process("doc"),
-                execs().with_status(101)
-                       .with_stderr("\
-cannot document a package where a library and a binary have the same name. \
-Consider renaming one or marking the target as `doc = false`
-"));
+                execs().with_status(0));
+    assert_that(&p.root().join("target/doc/foo/index.html"), existing_file());
+    assert_that(&p.root().join("target/doc/bin/foo/index.html"),
+                existing_file());
+    assert_that(&p.root().join("target/doc/index.html"), existing_file());
+    let index = read(&p.root().join("target/doc/index.html"));
+    assert!(index.contains("\
+<li><a href=\"foo/index.html\">foo</a> (lib of foo 0.0.1)</li>
+<li><a href=\"bin/foo/index.html\">foo</a> (bin of foo 0.0.1)</li>
+"), "{}", index);
 });
 
 test!(doc_dash_p {
@@ -525,3 +533,91 @@ test!(features {
     assert_that(&p.root().join("target/doc/foo/fn.foo.html"), existing_file());
     assert_that(&p.root().join("target/doc/bar/fn.bar.html"), existing_file());
 });
+
+test!(doc_index_lists_every_crate {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [dependencies.bar]
+            path = "bar"
+        "#)
+        .file("src/lib.rs", r#"
+            extern crate bar;
+            pub fn foo() {}
+        "#)
+        .file("src/bin/tool.rs", r#"
+            extern crate foo;
+            fn main() { foo::foo() }
+        "#)
+        .file("bar/Cargo.toml", r#"
+            [package]
+            name = "bar"
+            version = "0.1.0"
+            authors = []
+        "#)
+        .file("bar/src/lib.rs", r#"
+            pub fn bar() {}
+        "#);
+
+    assert_that(p.cargo_process("doc"),
+                execs().with_status(0));
+    let index = read(&p.root().join("target/doc/index.html"));
+    assert!(index.contains("\
+<li><a href=\"bar/index.html\">bar</a> (lib of bar 0.1.0)</li>
+<li><a href=\"foo/index.html\">foo</a> (lib of foo 0.0.1)</li>
+<li><a href=\"tool/index.html\">tool</a> (bin of foo 0.0.1)</li>
+"), "{}", index);
+    assert_that(&p.root().join("target/doc/tool/index.html"), existing_file());
+
+    assert_that(p.cargo("doc").arg("--no-deps"),
+                execs().with_status(0));
+    let index = read(&p.root().join("target/doc/index.html"));
+    assert!(!index.contains("bar/index.html"), "{}", index);
+});
+
+test!(doc_private_items {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [dependencies.bar]
+            path = "bar"
+        "#)
+        .file("src/lib.rs", r#"
+            extern crate bar;
+            fn private() {}
+        "#)
+        .file("bar/Cargo.toml", r#"
+            [package]
+            name = "bar"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("bar/src/lib.rs", r#"
+            fn hidden() {}
+        "#);
+
+    assert_that(p.cargo_process("doc").arg("--document-private-items")
+                 .arg("-v"),
+                execs().with_status(0)
+                       .with_stdout_contains(format!("\
+{running} `rustdoc src[..]lib.rs --crate-name foo [..]--document-private-items[..]`
+", running = RUNNING)));
+    assert_that(&p.root().join("target/doc/foo/fn.private.html"),
+                existing_file());
+    assert_that(&p.root().join("target/doc/bar/fn.hidden.html"),
+                is_not(existing_file()));
+});
+
+fn read(path: &Path) -> String {
+    let mut contents = String::new();
+    File::open(path).unwrap().read_to_string(&mut contents).unwrap();
+    contents
+}
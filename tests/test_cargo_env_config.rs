@@ -0,0 +1,90 @@
+use support::{project, execs};
+use hamcrest::{assert_that, existing_file};
+
+fn setup() {
+}
+
+test!(env_overrides_config_file {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [dependencies.bar]
+            git = "http://127.0.0.1:11/foo/bar"
+        "#)
+        .file("src/main.rs", "")
+        .file(".cargo/config", r#"
+            [net]
+            retry = 0
+        "#);
+
+    assert_that(p.cargo_process("build").arg("-v")
+                 .env("CARGO_NET_RETRY", "1"),
+                execs().with_status(101).with_stderr("\
+warning: spurious network error (1 tries remaining): [..]
+Unable to update http://127.0.0.1:11/foo/bar
+
+Caused by:
+  failed to clone into: [..]
+
+Caused by:
+  [..]
+"));
+});
+
+test!(env_integer_is_parsed {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.0"
+            authors = []
+        "#)
+        .file("src/lib.rs", "");
+
+    assert_that(p.cargo_process("build").env("CARGO_BUILD_JOBS", "2"),
+                execs().with_status(0));
+
+    assert_that(p.cargo("build").env("CARGO_BUILD_JOBS", "-1"),
+                execs().with_status(101).with_stderr("\
+build.jobs must be positive, but found -1 in \"environment variable \
+`CARGO_BUILD_JOBS`\"
+"));
+});
+
+test!(env_invalid_integer {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.0"
+            authors = []
+        "#)
+        .file("src/lib.rs", "");
+
+    assert_that(p.cargo_process("build").env("CARGO_BUILD_JOBS", "many"),
+                execs().with_status(101).with_stderr("\
+invalid configuration for key `build.jobs`
+expected an integer, but found `many` in environment variable \
+`CARGO_BUILD_JOBS`
+"));
+});
+
+test!(env_path_is_relative_to_cwd {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.0"
+            authors = []
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("build")
+                 .env("CARGO_BUILD_TARGET_DIR", "elsewhere"),
+                execs().with_status(0));
+    assert_that(&p.root().join("elsewhere/debug/foo"), existing_file());
+});
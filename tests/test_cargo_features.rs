@@ -4,7 +4,7 @@ use std::io::prelude::*;
 use support::{project, execs};
 use support::{COMPILING, FRESH};
 use support::paths::CargoPathExt;
-use hamcrest::assert_that;
+use hamcrest::{assert_that, existing_file, is_not};
 
 fn setup() {
 }
@@ -807,3 +807,166 @@ test!(activating_feature_activates_dep {
     assert_that(p.cargo_process("build").arg("--features").arg("a").arg("-v"),
                 execs().with_status(0));
 });
+
+test!(required_features_skip_bin {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [features]
+            a = []
+
+            [[bin]]
+            name = "foo"
+            required-features = ["a"]
+
+            [[bin]]
+            name = "bar"
+        "#)
+        .file("src/lib.rs", "")
+        .file("src/bin/foo.rs", "extern crate foo; fn main() {}")
+        .file("src/bin/bar.rs", "extern crate foo; fn main() {}");
+
+    assert_that(p.cargo_process("build"),
+                execs().with_status(0));
+    assert_that(&p.bin("bar"), existing_file());
+    assert_that(&p.bin("foo"), is_not(existing_file()));
+
+    assert_that(p.cargo("build").arg("--features").arg("a"),
+                execs().with_status(0));
+    assert_that(&p.bin("foo"), existing_file());
+});
+
+test!(required_features_default_feature {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [features]
+            default = ["a"]
+            a = []
+
+            [[bin]]
+            name = "foo"
+            path = "src/main.rs"
+            required-features = ["a"]
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("build"),
+                execs().with_status(0));
+    assert_that(&p.bin("foo"), existing_file());
+
+    assert_that(p.cargo("build").arg("--no-default-features"),
+                execs().with_status(0).with_stdout(""));
+});
+
+test!(required_features_named_target_errors {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [features]
+            a = []
+            b = []
+
+            [[example]]
+            name = "ex"
+            required-features = ["a", "b"]
+        "#)
+        .file("src/lib.rs", "")
+        .file("examples/ex.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("build").arg("--example").arg("ex"),
+                execs().with_status(101).with_stderr("\
+target `ex` requires the features: `a`, `b`
+Consider enabling them by passing e.g. `--features=\"a b\"`
+"));
+
+    assert_that(p.cargo("build").arg("--example").arg("ex")
+                 .arg("--features").arg("a b"),
+                execs().with_status(0));
+});
+
+test!(required_features_skip_tests {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [features]
+            a = []
+
+            [[test]]
+            name = "needs_a"
+            required-features = ["a"]
+        "#)
+        .file("src/lib.rs", "")
+        .file("tests/needs_a.rs", r#"
+            #[test]
+            fn foo() { panic!() }
+        "#);
+
+    assert_that(p.cargo_process("test"),
+                execs().with_status(0)
+                       .with_stdout_contains("running 0 tests"));
+    assert_that(p.cargo("test").arg("--features").arg("a"),
+                execs().with_status(101));
+});
+
+test!(required_features_lib_warning {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [features]
+            a = []
+
+            [lib]
+            required-features = ["a"]
+        "#)
+        .file("src/lib.rs", "");
+
+    assert_that(p.cargo_process("build"),
+                execs().with_status(0).with_stderr("\
+warning: required-features is ignored for the [lib] section
+"));
+});
+
+test!(required_features_run_reports_missing {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [features]
+            a = []
+
+            [[bin]]
+            name = "foo"
+            required-features = ["a"]
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("run"),
+                execs().with_status(101).with_stderr("\
+target `foo` requires the features: `a`
+Consider enabling them by passing e.g. `--features=\"a\"`
+"));
+});
@@ -0,0 +1,105 @@
+use std::fs;
+use std::path::PathBuf;
+
+use filetime::{self, FileTime};
+
+use support::{git, project, execs, ProjectBuilder, REMOVING};
+use support::paths;
+use hamcrest::{assert_that, existing_dir, is_not};
+
+fn setup() {
+}
+
+fn project_with_git_dep() -> ProjectBuilder {
+    let bar = git::new("bar", |project| {
+        project.file("Cargo.toml", r#"
+            [package]
+            name = "bar"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+    }).unwrap();
+
+    let p = project("foo")
+        .file("Cargo.toml", &format!(r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [dependencies.bar]
+            git = '{}'
+        "#, bar.url()))
+        .file("src/lib.rs", "");
+    assert_that(p.cargo_process("build"), execs().with_status(0));
+    p
+}
+
+fn git_db() -> PathBuf {
+    let root = paths::home().join(".cargo/git/db");
+    fs::read_dir(&root).unwrap().next().unwrap().unwrap().path()
+}
+
+fn age_by_days(path: &PathBuf, days: u64) {
+    let meta = fs::metadata(path).unwrap();
+    let mtime = FileTime::from_last_modification_time(&meta);
+    let old = FileTime::from_seconds_since_1970(
+        mtime.seconds_relative_to_1970() - days * 24 * 60 * 60, 0);
+    filetime::set_file_times(path, old, old).unwrap();
+}
+
+test!(removes_stale_databases {
+    let p = project_with_git_dep();
+    let db = git_db();
+    let checkouts = paths::home().join(".cargo/git/checkouts")
+                                 .join(db.file_name().unwrap());
+    assert_that(&checkouts, existing_dir());
+    age_by_days(&db, 100);
+
+    assert_that(p.cargo("git-gc"),
+                execs().with_status(0)
+                       .with_stdout(format!("{} [..]", REMOVING)));
+    assert_that(&db, is_not(existing_dir()));
+    assert_that(&checkouts, is_not(existing_dir()));
+
+    // It's just cloned again when needed
+    assert_that(p.cargo("build"), execs().with_status(0));
+    assert_that(&db, existing_dir());
+});
+
+test!(keeps_recently_used_databases {
+    let p = project_with_git_dep();
+    let db = git_db();
+    age_by_days(&db, 10);
+
+    assert_that(p.cargo("git-gc"),
+                execs().with_status(0).with_stdout(""));
+    assert_that(&db, existing_dir());
+
+    assert_that(p.cargo("git-gc").arg("--max-age").arg("5"),
+                execs().with_status(0)
+                       .with_stdout(format!("{} [..]", REMOVING)));
+    assert_that(&db, is_not(existing_dir()));
+});
+
+test!(dry_run_removes_nothing {
+    let p = project_with_git_dep();
+    let db = git_db();
+    age_by_days(&db, 100);
+
+    assert_that(p.cargo("git-gc").arg("--dry-run"),
+                execs().with_status(0).with_stdout("Would remove [..]"));
+    assert_that(&db, existing_dir());
+});
+
+test!(building_marks_database_as_used {
+    let p = project_with_git_dep();
+    let db = git_db();
+    age_by_days(&db, 100);
+
+    assert_that(p.cargo("build"), execs().with_status(0));
+    assert_that(p.cargo("git-gc"),
+                execs().with_status(0).with_stdout(""));
+    assert_that(&db, existing_dir());
+});
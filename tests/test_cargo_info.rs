@@ -0,0 +1,146 @@
+use std::fs::{self, File};
+use std::io::prelude::*;
+use std::path::PathBuf;
+
+use url::Url;
+
+use cargo::util::{process, ProcessBuilder};
+use support::UPDATING;
+use support::{execs, cargo_dir};
+use support::paths;
+use support::git::repo;
+
+use hamcrest::assert_that;
+
+fn registry_path() -> PathBuf { paths::root().join("registry") }
+fn registry() -> Url { Url::from_file_path(&*registry_path()).ok().unwrap() }
+fn api_path() -> PathBuf { paths::root().join("api") }
+fn api() -> Url { Url::from_file_path(&*api_path()).ok().unwrap() }
+
+fn setup() {
+    let config = paths::root().join(".cargo/config");
+    fs::create_dir_all(config.parent().unwrap()).unwrap();
+    File::create(&config).unwrap().write_all(format!(r#"
+        [registry]
+            index = "{reg}"
+    "#, reg = registry()).as_bytes()).unwrap();
+    fs::create_dir_all(&api_path().join("api/v1/crates")).unwrap();
+
+    repo(&registry_path())
+        .file("config.json", &format!(r#"{{
+            "dl": "{0}",
+            "api": "{0}"
+        }}"#, api()))
+        .build();
+}
+
+fn cargo_process(s: &str) -> ProcessBuilder {
+    let mut b = process(&cargo_dir().join("cargo"));
+    b.arg(s).cwd(&paths::root()).env("HOME", &paths::home());
+    b
+}
+
+test!(simple {
+    let contents = r#"{
+        "crate": {
+            "created_at": "2014-11-16T20:17:35Z",
+            "description": "Design by contract style assertions for Rust",
+            "documentation": null,
+            "downloads": 12,
+            "homepage": null,
+            "id": "hoare",
+            "keywords": [],
+            "max_version": "0.1.1",
+            "name": "hoare",
+            "repository": "https://github.com/nick29581/libhoare",
+            "updated_at": "2014-11-20T21:49:21Z",
+            "versions": [2, 1]
+        },
+        "versions": [{
+            "id": 2,
+            "crate": "hoare",
+            "num": "0.1.1",
+            "created_at": "2014-11-20T21:49:21Z",
+            "updated_at": "2014-11-20T21:49:21Z",
+            "downloads": 10,
+            "yanked": false,
+            "license": "MIT"
+        }, {
+            "id": 1,
+            "crate": "hoare",
+            "num": "0.1.0",
+            "created_at": "2014-11-16T20:17:35Z",
+            "updated_at": "2014-11-16T20:17:35Z",
+            "downloads": 2,
+            "yanked": true,
+            "license": null
+        }]
+    }"#;
+    File::create(&api_path().join("api/v1/crates/hoare")).unwrap()
+         .write_all(contents.as_bytes()).unwrap();
+
+    assert_that(cargo_process("info").arg("hoare"),
+                execs().with_status(0).with_stdout(format!("\
+{updating} registry `[..]`
+hoare v0.1.1
+Design by contract style assertions for Rust
+license: MIT
+repository: https://github.com/nick29581/libhoare
+downloads: 12
+versions:
+    0.1.1  2014-11-20  10 downloads
+    0.1.0  2014-11-16  2 downloads (yanked)
+", updating = UPDATING)));
+});
+
+test!(not_found {
+    assert_that(cargo_process("info").arg("missing"),
+                execs().with_status(101).with_stderr("\
+failed to retrieve crate `missing` from the registry: [..]
+"));
+});
+
+test!(missing_optional_fields {
+    let contents = r#"{
+        "crate": {
+            "description": null,
+            "id": "bare",
+            "max_version": "0.1.0",
+            "name": "bare"
+        },
+        "versions": [{
+            "id": 1,
+            "crate": "bare",
+            "num": "0.1.0",
+            "yanked": false
+        }]
+    }"#;
+    File::create(&api_path().join("api/v1/crates/bare")).unwrap()
+         .write_all(contents.as_bytes()).unwrap();
+
+    assert_that(cargo_process("info").arg("bare"),
+                execs().with_status(0).with_stdout(format!("\
+{updating} registry `[..]`
+bare v0.1.0
+versions:
+    0.1.0
+", updating = UPDATING)));
+});
+
+test!(malformed_response {
+    File::create(&api_path().join("api/v1/crates/broken")).unwrap()
+         .write_all(br#"{"crate": {"name": "broken"}}"#).unwrap();
+
+    assert_that(cargo_process("info").arg("broken"),
+                execs().with_status(101).with_stderr("\
+failed to retrieve crate `broken` from the registry: failed to decode the \
+registry's response: [..]
+"));
+});
+
+test!(help {
+    assert_that(cargo_process("info").arg("-h"),
+                execs().with_status(0));
+    assert_that(cargo_process("help").arg("info"),
+                execs().with_status(0));
+});
@@ -0,0 +1,113 @@
+use std::fs::{self, File};
+use std::io::prelude::*;
+use std::path::Path;
+use tempdir::TempDir;
+
+use support::{execs, paths, cargo_dir};
+use hamcrest::{assert_that, existing_file, existing_dir, is_not};
+
+use cargo::util::{process, ProcessBuilder};
+
+fn setup() {
+}
+
+fn cargo_process(s: &str) -> ProcessBuilder {
+    let mut p = process(&cargo_dir().join("cargo"));
+    p.arg(s).cwd(&paths::root()).env("HOME", &paths::home());
+    return p;
+}
+
+fn read(path: &Path) -> String {
+    let mut contents = String::new();
+    File::open(path).unwrap().read_to_string(&mut contents).unwrap();
+    contents
+}
+
+test!(simple_lib {
+    let td = TempDir::new("cargo").unwrap();
+    let dir = td.path().join("foo");
+    fs::create_dir(&dir).unwrap();
+
+    assert_that(cargo_process("init").cwd(&dir).env("USER", "foo"),
+                execs().with_status(0));
+
+    assert_that(&dir.join("Cargo.toml"), existing_file());
+    assert_that(&dir.join("src/lib.rs"), existing_file());
+    assert_that(&dir.join(".git"), existing_dir());
+    assert_eq!(read(&dir.join(".gitignore")), "target\nCargo.lock\n");
+
+    assert_that(cargo_process("build").cwd(&dir),
+                execs().with_status(0));
+});
+
+test!(keeps_existing_main {
+    let dir = paths::root().join("foo");
+    fs::create_dir_all(&dir.join("src")).unwrap();
+    File::create(&dir.join("src/main.rs")).unwrap().write_all(br#"
+        fn main() { println!("existing"); }
+    "#).unwrap();
+
+    assert_that(cargo_process("init").arg("foo").arg("--vcs").arg("none")
+                                     .env("USER", "foo"),
+                execs().with_status(0));
+
+    assert_that(&dir.join("src/lib.rs"), is_not(existing_file()));
+    assert_that(cargo_process("run").cwd(&dir),
+                execs().with_status(0).with_stdout_contains("existing"));
+});
+
+test!(keeps_existing_lib_and_main {
+    let dir = paths::root().join("foo");
+    fs::create_dir_all(&dir.join("src")).unwrap();
+    File::create(&dir.join("src/lib.rs")).unwrap()
+        .write_all(b"pub fn f() {}").unwrap();
+    File::create(&dir.join("src/main.rs")).unwrap()
+        .write_all(b"extern crate foo; fn main() { foo::f() }").unwrap();
+
+    assert_that(cargo_process("init").arg("foo").arg("--vcs").arg("none")
+                                     .env("USER", "foo"),
+                execs().with_status(0));
+
+    assert_eq!(read(&dir.join("src/lib.rs")), "pub fn f() {}");
+    assert_that(cargo_process("build").cwd(&dir),
+                execs().with_status(0));
+});
+
+test!(existing_git_repo {
+    let td = TempDir::new("cargo").unwrap();
+    let dir = td.path().join("foo");
+    fs::create_dir(&dir).unwrap();
+    ::git2::Repository::init(&dir).unwrap();
+    File::create(&dir.join(".gitignore")).unwrap()
+        .write_all(b"*.swp").unwrap();
+
+    assert_that(cargo_process("init").cwd(&dir).arg("--bin")
+                                     .env("USER", "foo"),
+                execs().with_status(0));
+
+    assert_that(&dir.join("src/main.rs"), existing_file());
+    assert_eq!(read(&dir.join(".gitignore")), "*.swp\ntarget\n");
+});
+
+test!(existing_hg_repo_is_not_replaced {
+    let td = TempDir::new("cargo").unwrap();
+    let dir = td.path().join("foo");
+    fs::create_dir_all(&dir.join(".hg")).unwrap();
+
+    assert_that(cargo_process("init").cwd(&dir).env("USER", "foo"),
+                execs().with_status(0));
+
+    assert_that(&dir.join(".git"), is_not(existing_dir()));
+    assert_eq!(read(&dir.join(".hgignore")), "target\nCargo.lock\n");
+});
+
+test!(already_a_project {
+    let dir = paths::root().join("foo");
+    fs::create_dir_all(&dir).unwrap();
+    File::create(&dir.join("Cargo.toml")).unwrap();
+
+    assert_that(cargo_process("init").arg("foo").env("USER", "foo"),
+                execs().with_status(101).with_stderr("\
+`cargo init` cannot be run on existing Cargo projects
+"));
+});
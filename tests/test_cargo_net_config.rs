@@ -0,0 +1,64 @@
+use support::{project, execs};
+use hamcrest::assert_that;
+
+fn setup() {
+}
+
+test!(net_retry_loads_from_config {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [dependencies.bar]
+            git = "http://127.0.0.1:11/foo/bar"
+        "#)
+        .file("src/main.rs", "")
+        .file(".cargo/config", r#"
+            [net]
+            retry = 1
+        "#);
+
+    assert_that(p.cargo_process("build").arg("-v"),
+                execs().with_status(101).with_stderr("\
+warning: spurious network error (1 tries remaining): [..]
+Unable to update http://127.0.0.1:11/foo/bar
+
+Caused by:
+  failed to clone into: [..]
+
+Caused by:
+  [..]
+"));
+});
+
+test!(net_retry_can_be_disabled {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [dependencies.bar]
+            git = "http://127.0.0.1:11/foo/bar"
+        "#)
+        .file("src/main.rs", "")
+        .file(".cargo/config", r#"
+            [net]
+            retry = 0
+        "#);
+
+    assert_that(p.cargo_process("build").arg("-v"),
+                execs().with_status(101).with_stderr("\
+Unable to update http://127.0.0.1:11/foo/bar
+
+Caused by:
+  failed to clone into: [..]
+
+Caused by:
+  [..]
+"));
+});
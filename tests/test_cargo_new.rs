@@ -1,9 +1,10 @@
 use std::fs::{self, File};
 use std::io::prelude::*;
 use std::env;
+use git2;
 use tempdir::TempDir;
 
-use support::{execs, paths, cargo_dir};
+use support::{execs, git, paths, cargo_dir};
 use support::paths::CargoPathExt;
 use hamcrest::{assert_that, existing_file, existing_dir, is_not};
 
@@ -285,3 +286,115 @@ Usage:
     cargo new [..]
 "));
 });
+
+fn read(path: &::std::path::Path) -> String {
+    let mut contents = String::new();
+    File::open(path).unwrap().read_to_string(&mut contents).unwrap();
+    contents
+}
+
+test!(template_directory {
+    let skel = paths::root().join("skel");
+    fs::create_dir_all(&skel.join("src/bin")).unwrap();
+    File::create(&skel.join("Cargo.toml")).unwrap().write_all(br#"
+        [package]
+        name = "{{name}}"
+        version = "1.0.0"
+        authors = [{{authors}}]
+    "#).unwrap();
+    File::create(&skel.join("src/lib.rs")).unwrap()
+        .write_all(b"//! The {{name}} service").unwrap();
+    File::create(&skel.join("src/bin/{{name}}-server.rs")).unwrap()
+        .write_all(b"fn main() {}").unwrap();
+
+    assert_that(cargo_process("new").arg("foo").arg("--vcs").arg("none")
+                                    .arg("--template").arg("skel")
+                                    .env("USER", "foo"),
+                execs().with_status(0));
+
+    assert_eq!(read(&paths::root().join("foo/Cargo.toml")), r#"
+        [package]
+        name = "foo"
+        version = "1.0.0"
+        authors = ["foo"]
+    "#);
+    assert_eq!(read(&paths::root().join("foo/src/lib.rs")),
+               "//! The foo service");
+    assert_that(&paths::root().join("foo/src/bin/foo-server.rs"),
+                existing_file());
+
+    assert_that(cargo_process("build").cwd(&paths::root().join("foo")),
+                execs().with_status(0));
+});
+
+test!(template_git_repository {
+    let skel = git::new("skel", |project| {
+        project.file("Cargo.toml", r#"
+            [package]
+            name = "{{name}}"
+            version = "0.1.0"
+            authors = []
+        "#)
+        .file("src/main.rs", "fn main() {}")
+        .file(".gitignore", "target\n*.swp\n")
+    }).unwrap();
+
+    let td = TempDir::new("cargo").unwrap();
+    assert_that(cargo_process("new").arg("foo").cwd(td.path().clone())
+                                    .arg("--template").arg(skel.url().to_string())
+                                    .env("USER", "foo"),
+                execs().with_status(0));
+
+    assert_that(&td.path().join("foo/src/main.rs"), existing_file());
+    assert_that(&td.path().join("foo/.git"), existing_dir());
+    // The template is a binary, so its lock file is kept
+    assert_eq!(read(&td.path().join("foo/.gitignore")), "\
+target
+*.swp
+");
+});
+
+test!(template_git_branch {
+    let skel = git::new("skel", |project| {
+        project.file("Cargo.toml", r#"
+            [package]
+            name = "{{name}}"
+            version = "0.1.0"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+    }).unwrap();
+    let repo = git2::Repository::open(&skel.root()).unwrap();
+    let head = repo.head().unwrap().target().unwrap();
+    let parent = repo.find_commit(head).unwrap();
+    let sig = repo.signature().unwrap();
+    repo.branch("service", &parent, false).unwrap();
+    repo.commit(Some("refs/heads/service"), &sig, &sig, "service",
+                &parent.tree().unwrap(), &[&parent]).unwrap();
+
+    let td = TempDir::new("cargo").unwrap();
+    assert_that(cargo_process("new").arg("foo").cwd(td.path().clone())
+                                    .arg("--template")
+                                    .arg(format!("{}#service", skel.url()))
+                                    .env("USER", "foo"),
+                execs().with_status(0));
+    assert_that(&td.path().join("foo/src/lib.rs"), existing_file());
+    assert_eq!(read(&td.path().join("foo/.gitignore")), "\
+target
+Cargo.lock
+");
+});
+
+test!(template_without_manifest {
+    fs::create_dir_all(&paths::root().join("skel")).unwrap();
+
+    assert_that(cargo_process("new").arg("foo").arg("--vcs").arg("none")
+                                    .arg("--template").arg("skel")
+                                    .env("USER", "foo"),
+                execs().with_status(101).with_stderr("\
+Failed to create project `foo` at `[..]foo`
+
+Caused by:
+  template `skel` does not contain a `Cargo.toml`
+"));
+});
@@ -61,6 +61,7 @@ src[..]main.rs
         let fname = f.header().path_bytes();
         let fname = &*fname;
         assert!(fname == b"foo-0.0.1/Cargo.toml" ||
+                fname == b"foo-0.0.1/Cargo.toml.orig" ||
                 fname == b"foo-0.0.1/src/main.rs",
                 "unexpected filename: {:?}", f.header().path())
     }
@@ -421,6 +422,7 @@ src[..]main.rs
         let fname = f.header().path_bytes();
         let fname = &*fname;
         assert!(fname == b"nested-0.0.1/Cargo.toml" ||
+                fname == b"nested-0.0.1/Cargo.toml.orig" ||
                 fname == b"nested-0.0.1/src/main.rs",
                 "unexpected filename: {:?}", f.header().path())
     }
@@ -449,3 +451,161 @@ Caused by:
   cannot package a filename with a special character `:`: src/:foo
 "));
 });
+
+test!(package_unpublishable {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            license = "MIT"
+            description = "foo"
+            publish = false
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("package"),
+                execs().with_status(101).with_stderr("\
+`foo` cannot be packaged for publishing.
+`publish` is set to `false` or an empty list in Cargo.toml and prevents \
+publishing.
+"));
+});
+
+test!(package_normalizes_manifest {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            license = "MIT"
+            description = "foo"
+
+            [dependencies.bar]
+            path = "bar"
+            version = "0.0.1"
+
+            [dev-dependencies.baz]
+            path = "baz"
+        "#)
+        .file("src/main.rs", "fn main() {}")
+        .file("bar/Cargo.toml", r#"
+            [package]
+            name = "bar"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("bar/src/lib.rs", "")
+        .file("baz/Cargo.toml", r#"
+            [package]
+            name = "baz"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("baz/src/lib.rs", "");
+
+    assert_that(p.cargo_process("package").arg("--no-verify"),
+                execs().with_status(0));
+
+    let f = File::open(&p.root().join("target/package/foo-0.0.1.crate")).unwrap();
+    let mut rdr = GzDecoder::new(f).unwrap();
+    let mut contents = Vec::new();
+    rdr.read_to_end(&mut contents).unwrap();
+    let ar = Archive::new(Cursor::new(contents));
+    let mut manifest = None;
+    let mut orig = None;
+    for f in ar.files().unwrap() {
+        let mut f = f.unwrap();
+        let fname = f.header().path_bytes().into_owned();
+        let mut s = String::new();
+        f.read_to_string(&mut s).unwrap();
+        if fname == b"foo-0.0.1/Cargo.toml" {
+            manifest = Some(s);
+        } else if fname == b"foo-0.0.1/Cargo.toml.orig" {
+            orig = Some(s);
+        }
+    }
+
+    let manifest = manifest.expect("normalized manifest not archived");
+    assert!(!manifest.contains("path"), "{}", manifest);
+    assert!(!manifest.contains("baz"), "{}", manifest);
+    assert!(manifest.contains("bar"), "{}", manifest);
+    let orig = orig.expect("original manifest not archived");
+    assert!(orig.contains("path = \"bar\""), "{}", orig);
+});
+
+test!(package_rejects_path_dependency_without_version {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            license = "MIT"
+            description = "foo"
+
+            [dependencies.bar]
+            path = "bar"
+        "#)
+        .file("src/main.rs", "fn main() {}")
+        .file("bar/Cargo.toml", r#"
+            [package]
+            name = "bar"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("bar/src/lib.rs", "");
+
+    assert_that(p.cargo_process("package").arg("--no-verify"),
+                execs().with_status(101).with_stderr_contains("\
+all path dependencies must have a version specified when packaging.
+dependency `bar` does not specify a version
+"));
+});
+
+test!(package_replaces_registry_name_with_index {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            license = "MIT"
+            description = "foo"
+
+            [dependencies.bar]
+            version = "0.0.1"
+            registry = "alternative"
+        "#)
+        .file("src/main.rs", "fn main() {}");
+    Package::new("bar", "0.0.1").alternative(true).publish();
+
+    // Verifying builds from the normalized manifest, so `registry-index`
+    // must resolve on its own.
+    assert_that(p.cargo_process("package"),
+                execs().with_status(0));
+
+    let f = File::open(&p.root().join("target/package/foo-0.0.1.crate")).unwrap();
+    let mut rdr = GzDecoder::new(f).unwrap();
+    let mut contents = Vec::new();
+    rdr.read_to_end(&mut contents).unwrap();
+    let ar = Archive::new(Cursor::new(contents));
+    let mut manifest = None;
+    for f in ar.files().unwrap() {
+        let mut f = f.unwrap();
+        let fname = f.header().path_bytes().into_owned();
+        if fname == b"foo-0.0.1/Cargo.toml" {
+            let mut s = String::new();
+            f.read_to_string(&mut s).unwrap();
+            manifest = Some(s);
+        }
+    }
+
+    let manifest = manifest.expect("normalized manifest not archived");
+    assert!(!manifest.contains("registry = "), "{}", manifest);
+    assert!(manifest.contains(&format!("registry-index = \"{}\"",
+                                       registry::alt_registry())),
+            "{}", manifest);
+});
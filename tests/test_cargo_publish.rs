@@ -26,6 +26,10 @@ fn setup() {
         [registry]
             index = "{reg}"
             token = "api-token"
+
+        [registries.alternative]
+            index = "{reg}"
+            token = "alt-token"
     "#, reg = registry()).as_bytes()).unwrap();
     fs::create_dir_all(&upload_path().join("api/v1/crates")).unwrap();
 
@@ -83,6 +87,7 @@ test!(simple {
         let fname = file.header().path_bytes();
         let fname = &*fname;
         assert!(fname == b"foo-0.0.1/Cargo.toml" ||
+                fname == b"foo-0.0.1/Cargo.toml.orig" ||
                 fname == b"foo-0.0.1/src/main.rs",
                 "unexpected filename: {:?}", file.header().path());
     }
@@ -138,3 +143,186 @@ all path dependencies must have a version specified when publishing.
 dependency `bar` does not specify a version
 "));
 });
+
+test!(publish_to_named_registry {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            license = "MIT"
+            description = "foo"
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("publish").arg("--no-verify")
+                 .arg("--registry").arg("alternative"),
+                execs().with_status(0).with_stdout(&format!("\
+{updating} registry `{reg}`
+{packaging} foo v0.0.1 ({dir})
+{uploading} foo v0.0.1 ({dir})
+",
+        updating = UPDATING,
+        uploading = UPLOADING,
+        packaging = PACKAGING,
+        dir = p.url(),
+        reg = registry())));
+    assert!(fs::metadata(&upload_path().join("api/v1/crates/new")).is_ok());
+});
+
+test!(publish_to_unknown_registry {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            license = "MIT"
+            description = "foo"
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("publish").arg("--no-verify")
+                 .arg("--registry").arg("missing"),
+                execs().with_status(101).with_stderr("\
+no index found for registry: `missing`
+"));
+});
+
+test!(publish_host_and_registry {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            license = "MIT"
+            description = "foo"
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("publish").arg("--no-verify")
+                 .arg("--host").arg(registry().to_string())
+                 .arg("--registry").arg("alternative"),
+                execs().with_status(101).with_stderr("\
+a registry index and `--registry` cannot both be specified
+"));
+});
+
+test!(unpublishable_crate {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            license = "MIT"
+            description = "foo"
+            publish = false
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("publish").arg("--no-verify"),
+                execs().with_status(101).with_stderr("\
+`foo` cannot be published.
+`publish` is set to `false` or an empty list in Cargo.toml and prevents \
+publishing.
+"));
+});
+
+test!(registry_not_in_publish_list {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            license = "MIT"
+            description = "foo"
+            publish = ["alternative"]
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("publish").arg("--no-verify"),
+                execs().with_status(101).with_stderr("\
+`foo` cannot be published.
+The registry `crates-io` is not listed in the `publish` value in Cargo.toml, \
+which only allows: `alternative`
+"));
+
+    assert_that(p.cargo("publish").arg("--no-verify")
+                 .arg("--registry").arg("alternative"),
+                execs().with_status(0));
+});
+
+test!(dry_run {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            license = "MIT"
+            description = "foo"
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("publish").arg("--dry-run"),
+                execs().with_status(0)
+                       .with_stdout_contains("  \"name\": \"foo\",")
+                       .with_stdout_contains("tarball: [..]foo-0.0.1.crate")
+                       .with_stderr("\
+warning: aborting upload due to dry run
+"));
+
+    // Nothing was uploaded, nor claimed to be.
+    assert!(fs::metadata(&upload_path().join("api/v1/crates/new")).is_err());
+    let output = p.cargo("publish").arg("--dry-run").exec_with_output()
+                  .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains(UPLOADING), "{}", stdout);
+    assert!(!stdout.contains(UPDATING), "{}", stdout);
+});
+
+test!(dry_run_needs_no_token_or_network {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            license = "MIT"
+            description = "foo"
+        "#)
+        .file("src/main.rs", "fn main() {}")
+        .file(".cargo/config", r#"
+            [registry]
+            index = "file:///path/to/nowhere"
+        "#);
+
+    assert_that(p.cargo_process("publish").arg("--dry-run"),
+                execs().with_status(0)
+                       .with_stdout_contains("tarball: [..]foo-0.0.1.crate")
+                       .with_stderr("\
+warning: aborting upload due to dry run
+"));
+});
+
+test!(dry_run_checks_license_file {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+            license-file = "LICENSE"
+            description = "foo"
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("publish").arg("--dry-run").arg("--no-verify"),
+                execs().with_status(101).with_stderr("\
+the license file `LICENSE` does not exist
+"));
+});
@@ -1,5 +1,6 @@
 use std::fs::{self, File};
 use std::io::prelude::*;
+use std::path::Path;
 use cargo::util::process;
 
 use support::{project, execs, cargo_dir};
@@ -762,6 +763,46 @@ test!(fetch_downloads {
 ", updating = UPDATING, downloading = DOWNLOADING)));
 });
 
+test!(fetch_downloads_many_crates {
+    let mut deps = String::new();
+    for i in 0..12 {
+        let name = format!("dep{}", i);
+        Package::new(&name, "0.1.0").publish();
+        deps.push_str(&format!("{} = \"0.1.0\"\n", name));
+    }
+    let p = project("foo")
+        .file("Cargo.toml", &format!(r#"
+            [project]
+            name = "foo"
+            version = "0.5.0"
+            authors = []
+
+            [dependencies]
+            {}
+        "#, deps))
+        .file("src/main.rs", "fn main() {}");
+    p.build();
+
+    let mut expected = execs().with_status(0);
+    for i in 0..12 {
+        expected = expected.with_stdout_contains(format!(
+            "{} dep{} v0.1.0 (registry [..])", DOWNLOADING, i));
+    }
+    assert_that(p.cargo("fetch"), expected);
+
+    // Every crate made it into the cache and no partial downloads are left
+    let cache = paths::home().join(".cargo/registry/cache");
+    let dir = fs::read_dir(&cache).unwrap().next().unwrap().unwrap().path();
+    let mut names = fs::read_dir(&dir).unwrap().map(|e| {
+        e.unwrap().file_name().into_string().unwrap()
+    }).collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names.len(), 12);
+    assert!(names.iter().all(|n| n.ends_with(".crate")), "{:?}", names);
+
+    assert_that(p.cargo("build"), execs().with_status(0));
+});
+
 test!(update_transitive_dependency {
     let p = project("foo")
         .file("Cargo.toml", r#"
@@ -936,3 +977,194 @@ test!(bundled_crate_in_registry {
 
     assert_that(p.cargo("run"), execs().with_status(0));
 });
+
+test!(depend_on_alt_registry {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [dependencies.bar]
+            version = "0.0.1"
+            registry = "alternative"
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    Package::new("bar", "0.0.1").alternative(true).publish();
+
+    assert_that(p.cargo_process("build"),
+                execs().with_status(0).with_stdout(&format!("\
+{updating} registry `{reg}`
+{downloading} bar v0.0.1 (registry {reg})
+{compiling} bar v0.0.1 (registry {reg})
+{compiling} foo v0.0.1 ({dir})
+",
+        updating = UPDATING,
+        downloading = DOWNLOADING,
+        compiling = COMPILING,
+        dir = p.url(),
+        reg = registry::alt_registry())));
+});
+
+test!(depend_on_alt_registry_and_default {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [dependencies]
+            baz = "0.0.1"
+
+            [dependencies.bar]
+            version = "0.0.1"
+            registry = "alternative"
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    Package::new("bar", "0.0.1").alternative(true).publish();
+    Package::new("baz", "0.0.1").publish();
+
+    assert_that(p.cargo_process("build"),
+                execs().with_status(0)
+                       .with_stdout_contains(&format!("\
+{compiling} bar v0.0.1 (registry {reg})", compiling = COMPILING,
+                                              reg = registry::alt_registry()))
+                       .with_stdout_contains(&format!("\
+{compiling} baz v0.0.1 (registry {reg})", compiling = COMPILING,
+                                              reg = registry::registry())));
+});
+
+test!(unknown_alt_registry {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [dependencies.bar]
+            version = "0.0.1"
+            registry = "missing"
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    Package::new("bar", "0.0.1").alternative(true).publish();
+
+    assert_that(p.cargo_process("build"),
+                execs().with_status(101).with_stderr("\
+failed to parse manifest at `[..]`
+
+Caused by:
+  no index found for registry: `missing`
+"));
+});
+
+test!(alt_registry_and_git_is_ambiguous {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [dependencies.bar]
+            git = "https://example.com/bar"
+            registry = "alternative"
+        "#)
+        .file("src/main.rs", "fn main() {}");
+
+    assert_that(p.cargo_process("build"),
+                execs().with_status(101).with_stderr("\
+failed to parse manifest at `[..]`
+
+Caused by:
+  dependency (bar) specification is ambiguous. Only one of `git` or \
+`registry` is allowed.
+"));
+});
+
+test!(login_saves_token_to_credentials {
+    fs::create_dir_all(&paths::home().join(".cargo")).unwrap();
+    let cargo = || {
+        let mut p = process(&cargo_dir().join("cargo"));
+        p.cwd(&paths::root()).env("HOME", &paths::home());
+        p
+    };
+    let credentials = paths::home().join(".cargo/credentials");
+
+    assert_that(cargo().arg("login").arg("secret-token"),
+                execs().with_status(0));
+    let mut contents = String::new();
+    File::open(&credentials).unwrap().read_to_string(&mut contents).unwrap();
+    assert!(contents.contains("token = \"secret-token\""), "{}", contents);
+    assert!(fs::metadata(&paths::home().join(".cargo/config")).is_err());
+
+    assert_private(&credentials);
+
+    assert_that(cargo().arg("logout"),
+                execs().with_status(0).with_stdout("\
+[..]Logout token for `crates-io` has been removed from local storage
+"));
+    let mut contents = String::new();
+    File::open(&credentials).unwrap().read_to_string(&mut contents).unwrap();
+    assert!(!contents.contains("secret-token"), "{}", contents);
+
+    assert_that(cargo().arg("logout"),
+                execs().with_status(0).with_stdout("\
+[..]Logout not currently logged in to `crates-io`
+"));
+});
+
+#[cfg(unix)]
+fn assert_private(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = fs::metadata(path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[cfg(not(unix))]
+fn assert_private(_path: &Path) {}
+
+#[cfg(unix)]
+test!(login_with_credential_process {
+    use std::os::unix::fs::PermissionsExt;
+
+    let helper = paths::root().join("cred-helper");
+    File::create(&helper).unwrap().write_all(br#"#!/bin/sh
+store="$(dirname "$0")/stored-$CARGO_REGISTRY_NAME"
+case "$1" in
+    store) cat > "$store" ;;
+    get) cat "$store" ;;
+    erase) rm "$store" ;;
+esac
+"#).unwrap();
+    fs::set_permissions(&helper, fs::Permissions::from_mode(0o755)).unwrap();
+    fs::create_dir_all(&paths::home().join(".cargo")).unwrap();
+    File::create(&paths::home().join(".cargo/config")).unwrap()
+        .write_all(format!(r#"
+            [registry]
+            credential-process = "{}"
+        "#, helper.display()).as_bytes()).unwrap();
+
+    let cargo = || {
+        let mut p = process(&cargo_dir().join("cargo"));
+        p.cwd(&paths::root()).env("HOME", &paths::home());
+        p
+    };
+    let stored = paths::root().join("stored-crates-io");
+
+    assert_that(cargo().arg("login").arg("secret-token"),
+                execs().with_status(0));
+    let mut contents = String::new();
+    File::open(&stored).unwrap().read_to_string(&mut contents).unwrap();
+    assert_eq!(contents.trim(), "secret-token");
+    assert!(fs::metadata(&paths::home().join(".cargo/credentials")).is_err());
+
+    assert_that(cargo().arg("logout"),
+                execs().with_status(0));
+    assert!(fs::metadata(&stored).is_err());
+});
@@ -510,3 +510,45 @@ hello
         sep = SEP
         )));
 });
+
+#[cfg(unix)]
+test!(run_example_through_runner {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [project]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+        .file("examples/a.rs", r#"
+            fn main() { println!("example {:?}", std::env::args().nth(1)); }
+        "#)
+        .file("runner", r#"#!/bin/sh
+echo "runner $1"
+shift
+exec "$@"
+"#)
+        .file(".cargo/config", &format!(r#"
+            [target.{}]
+            runner = "./runner --emulate"
+        "#, ::rustc_host()));
+    p.build();
+    fs::set_permissions(&p.root().join("runner"),
+                        fs::Permissions::from_mode(0o755)).unwrap();
+
+    assert_that(p.cargo("run").arg("--example").arg("a").arg("arg"),
+                execs().with_status(0).with_stdout(&format!("\
+{compiling} foo v0.0.1 ({dir})
+{running} `[..]runner --emulate target{sep}debug{sep}examples{sep}a[..] arg`
+runner --emulate
+example Some(\"arg\")
+",
+        compiling = COMPILING,
+        running = RUNNING,
+        dir = path2url(p.root()),
+        sep = SEP)));
+});
@@ -76,7 +76,8 @@ test!(simple {
     // from source there anyway!
     File::create(&base).unwrap().write_all(contents.as_bytes()).unwrap();
     if !cfg!(windows) {
-        File::create(&base.with_file_name("crates?q=postgres")).unwrap()
+        File::create(&base.with_file_name("crates?q=postgres&page=1&per_page=10"))
+             .unwrap()
              .write_all(contents.as_bytes()).unwrap();
     }
 
@@ -86,6 +87,121 @@ test!(simple {
 hoare (0.1.1)    Design by contract style assertions for Rust", updating = UPDATING)));
 });
 
+fn search_results(total: u32) -> String {
+    format!(r#"{{
+        "crates": [{{
+            "description": "Design by contract style assertions for Rust",
+            "downloads": 2,
+            "max_version": "0.1.1",
+            "name": "hoare"
+        }}, {{
+            "description": null,
+            "downloads": 7,
+            "max_version": "1.0.0",
+            "name": "postgres"
+        }}],
+        "meta": {{
+            "total": {}
+        }}
+    }}"#, total)
+}
+
+test!(limit_shows_total {
+    let base = api_path().join("api/v1/crates");
+    File::create(&base).unwrap()
+         .write_all(search_results(5).as_bytes()).unwrap();
+    if !cfg!(windows) {
+        File::create(&base.with_file_name("crates?q=postgres&page=1&per_page=2"))
+             .unwrap().write_all(search_results(5).as_bytes()).unwrap();
+    }
+
+    assert_that(cargo_process("search").arg("postgres").arg("--limit").arg("2"),
+                execs().with_status(0).with_stdout(format!("\
+{updating} registry `[..]`
+hoare (0.1.1)    Design by contract style assertions for Rust
+postgres (1.0.0)
+... and 3 crates more (use --limit N to see more)
+", updating = UPDATING)));
+});
+
+test!(json_output {
+    let base = api_path().join("api/v1/crates");
+    File::create(&base).unwrap()
+         .write_all(search_results(2).as_bytes()).unwrap();
+    if !cfg!(windows) {
+        File::create(&base.with_file_name("crates?q=postgres&page=1&per_page=10"))
+             .unwrap().write_all(search_results(2).as_bytes()).unwrap();
+    }
+
+    assert_that(cargo_process("search").arg("postgres").arg("--json"),
+                execs().with_status(0).with_stdout_contains("\
+{\"crates\":[{\"name\":\"hoare\",[..]}],\"total\":2}"));
+});
+
+test!(search_does_not_run_credential_process {
+    let base = api_path().join("api/v1/crates");
+    File::create(&base).unwrap()
+         .write_all(search_results(2).as_bytes()).unwrap();
+    if !cfg!(windows) {
+        File::create(&base.with_file_name("crates?q=postgres&page=1&per_page=10"))
+             .unwrap().write_all(search_results(2).as_bytes()).unwrap();
+    }
+    File::create(&paths::root().join(".cargo/config")).unwrap()
+         .write_all(format!(r#"
+        [registry]
+            index = "{reg}"
+            credential-process = "does-not-exist"
+    "#, reg = registry()).as_bytes()).unwrap();
+
+    assert_that(cargo_process("search").arg("postgres"),
+                execs().with_status(0)
+                       .with_stdout_contains("postgres (1.0.0)"));
+});
+
+test!(missing_meta {
+    let contents = r#"{
+        "crates": [{
+            "description": null,
+            "max_version": "1.0.0",
+            "name": "postgres"
+        }]
+    }"#;
+    let base = api_path().join("api/v1/crates");
+    File::create(&base).unwrap().write_all(contents.as_bytes()).unwrap();
+    if !cfg!(windows) {
+        File::create(&base.with_file_name("crates?q=postgres&page=1&per_page=10"))
+             .unwrap().write_all(contents.as_bytes()).unwrap();
+    }
+
+    assert_that(cargo_process("search").arg("postgres"),
+                execs().with_status(0).with_stdout(format!("\
+{updating} registry `[..]`
+postgres (1.0.0)
+", updating = UPDATING)));
+});
+
+test!(malformed_response {
+    let contents = r#"{"crates": "nope"}"#;
+    let base = api_path().join("api/v1/crates");
+    File::create(&base).unwrap().write_all(contents.as_bytes()).unwrap();
+    if !cfg!(windows) {
+        File::create(&base.with_file_name("crates?q=postgres&page=1&per_page=10"))
+             .unwrap().write_all(contents.as_bytes()).unwrap();
+    }
+
+    assert_that(cargo_process("search").arg("postgres"),
+                execs().with_status(101).with_stderr_contains("\
+failed to retrieve search results from the registry[..]failed to decode the \
+registry's response: [..]"));
+});
+
+test!(unknown_sort_order {
+    assert_that(cargo_process("search").arg("postgres").arg("--sort").arg("alpha"),
+                execs().with_status(101).with_stderr("\
+unknown sort order `alpha`, expected one of `relevance`, `downloads` or `recent`
+"));
+});
+
 test!(help {
     assert_that(cargo_process("search").arg("-h"),
                 execs().with_status(0));
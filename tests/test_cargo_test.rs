@@ -2080,3 +2080,428 @@ test!(selective_test_optional_dep {
 {running} `rustc a[..]src[..]lib.rs [..]`
 ", compiling = COMPILING, running = RUNNING)));
 });
+
+test!(test_all_tests_honors_test_flag {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [[test]]
+            name = "a"
+
+            [[test]]
+            name = "b"
+            test = false
+        "#)
+        .file("src/lib.rs", "")
+        .file("tests/a.rs", "#[test] fn a() {}")
+        .file("tests/b.rs", "#[test] fn b() { panic!() }");
+
+    assert_that(p.cargo_process("test").arg("--tests"),
+                execs().with_status(0)
+                       .with_stdout_contains(format!("\
+{running} target[..]a-[..]", running = RUNNING)));
+});
+
+test!(test_bins_only {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "#[test] fn lib() { panic!() }")
+        .file("src/bin/a.rs", "fn main() {} #[test] fn a() {}")
+        .file("src/bin/b.rs", "fn main() {} #[test] fn b() {}");
+
+    assert_that(p.cargo_process("test").arg("--bins"),
+                execs().with_status(0)
+                       .with_stdout_contains("test a ... ok")
+                       .with_stdout_contains("test b ... ok"));
+});
+
+test!(list_tests_in_every_target {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", r#"
+            /// ```
+            /// foo::foo();
+            /// ```
+            pub fn foo() {}
+
+            #[test]
+            fn lib_test() {}
+        "#)
+        .file("src/main.rs", r#"
+            fn main() {}
+
+            #[test]
+            fn bin_test() {}
+        "#)
+        .file("tests/integration.rs", r#"
+            #[test]
+            fn first() {}
+
+            #[test]
+            fn second() {}
+        "#);
+
+    assert_that(p.cargo_process("test").arg("--list"),
+                execs().with_status(0)
+                       .with_stdout_contains("bin foo: bin_test")
+                       .with_stdout_contains("lib foo: lib_test")
+                       .with_stdout_contains("\
+test integration: first
+test integration: second
+")
+                       .with_stdout_contains("doc foo: [..]"));
+});
+
+test!(exact_only_runs_named_test {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", r#"
+            #[test]
+            fn foo() {}
+
+            #[test]
+            fn foo_bar() {}
+        "#);
+
+    assert_that(p.cargo_process("test").arg("--lib").arg("--exact")
+                 .arg("--").arg("foo"),
+                execs().with_status(0)
+                       .with_stdout_contains("\
+running 1 test
+test foo ... ok
+"));
+});
+
+test!(list_and_exact_skip_harnessless_targets {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+
+            [[test]]
+            name = "plain"
+            harness = false
+        "#)
+        .file("src/lib.rs", r#"
+            #[test]
+            fn foo() {}
+        "#)
+        .file("tests/plain.rs", r#"
+            fn main() {
+                if std::env::args().any(|a| a.starts_with("--")) {
+                    std::process::exit(1);
+                }
+                println!("plain ran");
+            }
+        "#);
+
+    assert_that(p.cargo_process("test").arg("--list"),
+                execs().with_status(0)
+                       .with_stdout_contains("lib foo: foo"));
+    assert_that(p.cargo("test").arg("--exact").arg("--").arg("foo"),
+                execs().with_status(0)
+                       .with_stdout_contains("test foo ... ok")
+                       .with_stdout_contains("plain ran"));
+});
+
+test!(failed_tests_print_rerun_command {
+    if !::can_panic() { return }
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+        .file("tests/math.rs", r#"
+            #[test]
+            fn adds() {}
+
+            #[test]
+            fn subtracts() { assert_eq!(1 - 1, 1) }
+        "#);
+
+    assert_that(p.cargo_process("test").arg("--release"),
+                execs().with_status(101)
+                       .with_stderr_contains("\
+To rerun the failed tests, run:
+    cargo test --test math --release -- --exact subtracts
+"));
+});
+
+test!(failed_doc_test_rerun_command {
+    if !::can_panic() { return }
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", r#"
+            /// ```
+            /// assert!(false);
+            /// ```
+            pub fn foo() {}
+        "#);
+
+    assert_that(p.cargo_process("test"),
+                execs().with_status(101)
+                       .with_stderr_contains("\
+To rerun the failed tests, run:
+    cargo test -- --exact [..]  # doc tests can't be run on their own
+"));
+});
+
+test!(test_jobs_runs_binaries_in_parallel {
+    if !::can_panic() { return }
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+        .file("tests/a.rs", r#"
+            #[test]
+            fn a() { std::thread::sleep_ms(500) }
+        "#)
+        .file("tests/b.rs", r#"
+            #[test]
+            fn b() { panic!() }
+        "#)
+        .file("tests/c.rs", r#"
+            #[test]
+            fn c() { std::thread::sleep_ms(500) }
+        "#);
+
+    assert_that(p.cargo_process("test").arg("--test-jobs").arg("3")
+                 .arg("--no-fail-fast"),
+                execs().with_status(101)
+                       .with_stdout_contains(format!("\
+{running} target[..]a-[..]
+
+running 1 test
+test a ... ok
+
+test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured
+", running = RUNNING))
+                       .with_stdout_contains(format!("\
+{running} target[..]c-[..]
+
+running 1 test
+test c ... ok
+", running = RUNNING))
+                       .with_stdout_contains("test b ... FAILED")
+                       .with_stderr_contains("\
+    cargo test --test b -- --exact b
+"));
+});
+
+test!(test_jobs_from_config {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+        .file(".cargo/config", r#"
+            [test]
+            jobs = 0
+        "#);
+
+    assert_that(p.cargo_process("test"),
+                execs().with_status(101).with_stderr("\
+test.jobs must be positive, but found 0 in [..]
+"));
+});
+
+test!(report_junit {
+    if !::can_panic() { return }
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "")
+        .file("tests/math.rs", r#"
+            #[test]
+            fn adds() {}
+
+            #[test]
+            #[ignore]
+            fn multiplies() {}
+
+            #[test]
+            fn subtracts() { println!("<oops>"); assert_eq!(1 - 1, 1) }
+        "#);
+
+    assert_that(p.cargo_process("test").arg("--test").arg("math")
+                 .arg("--report").arg("junit=target/report.xml"),
+                execs().with_status(101));
+
+    let mut report = String::new();
+    File::open(p.root().join("target/report.xml")).unwrap()
+        .read_to_string(&mut report).unwrap();
+    assert!(report.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                                <testsuites>\n  <testsuite name=\"test math\" \
+                                package=\"foo\" tests=\"3\" failures=\"1\" \
+                                skipped=\"1\" time=\""), "{}", report);
+    assert!(report.contains("\
+    <testcase name=\"adds\" classname=\"foo::test math\" time=\""),
+            "{}", report);
+    assert!(report.contains("\"/>
+    <testcase name=\"multiplies\" classname=\"foo::test math\" time=\""),
+            "{}", report);
+    assert!(report.contains("\">
+      <skipped/>
+    </testcase>
+    <testcase name=\"subtracts\" classname=\"foo::test math\" time=\""),
+            "{}", report);
+    assert!(report.contains("\">
+      <failure message=\"test failed\"><![CDATA[<oops>"), "{}", report);
+    assert!(report.ends_with("</failure>\n    </testcase>\n  </testsuite>\n\
+                              </testsuites>\n"), "{}", report);
+});
+
+test!(report_json_includes_doc_tests {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", r#"
+            /// ```
+            /// foo::foo();
+            /// ```
+            pub fn foo() {}
+
+            #[test]
+            fn it_works() {}
+        "#);
+
+    assert_that(p.cargo_process("test").arg("--report").arg("json=report.json"),
+                execs().with_status(0));
+
+    let mut report = String::new();
+    File::open(p.root().join("report.json")).unwrap()
+        .read_to_string(&mut report).unwrap();
+    assert!(report.starts_with("[{\"package\":\"foo\",\"target\":\"lib foo\",\
+                                \"time\":"), "{}", report);
+    assert!(report.contains("\"tests\":[{\"name\":\"it_works\",\
+                             \"outcome\":\"passed\",\"stdout\":null,\
+                             \"time\":"), "{}", report);
+    assert!(report.contains("}]},{\"package\":\"foo\",\"target\":\"doc foo\","),
+            "{}", report);
+    assert!(report.contains("\"outcome\":\"passed\""), "{}", report);
+});
+
+test!(report_with_colored_output {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", r#"
+            #[test]
+            fn it_works() {}
+        "#);
+
+    assert_that(p.cargo_process("test").arg("--lib")
+                 .arg("--report").arg("json=report.json")
+                 .arg("--").arg("--color").arg("always"),
+                execs().with_status(0));
+
+    let mut report = String::new();
+    File::open(p.root().join("report.json")).unwrap()
+        .read_to_string(&mut report).unwrap();
+    assert!(report.contains("\"tests\":[{\"name\":\"it_works\",\
+                             \"outcome\":\"passed\",\"stdout\":null,\
+                             \"time\":"), "{}", report);
+});
+
+test!(report_needs_a_format {
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", "");
+
+    assert_that(p.cargo_process("test").arg("--report").arg("xml=report.xml"),
+                execs().with_status(1).with_stderr_contains("\
+[..]expected `junit=PATH` or `json=PATH`[..]"));
+});
+
+#[cfg(unix)]
+test!(test_binaries_run_through_runner {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    let p = project("foo")
+        .file("Cargo.toml", r#"
+            [package]
+            name = "foo"
+            version = "0.0.1"
+            authors = []
+        "#)
+        .file("src/lib.rs", r#"
+            #[test]
+            fn it_works() {}
+        "#)
+        .file("runner", r#"#!/bin/sh
+echo "runner $1"
+shift
+exec "$@"
+"#)
+        .file(".cargo/config", &format!(r#"
+            [target.{}]
+            runner = ["./runner", "--emulate"]
+        "#, ::rustc_host()));
+    p.build();
+    fs::set_permissions(&p.root().join("runner"),
+                        fs::Permissions::from_mode(0o755)).unwrap();
+
+    assert_that(p.cargo("test").arg("--lib").arg("-v"),
+                execs().with_status(0)
+                       .with_stdout_contains(format!("\
+{running} `[..]runner --emulate {dir}/target/debug/foo-[..]`
+runner --emulate
+
+running 1 test
+test it_works ... ok
+", running = RUNNING, dir = p.root().display())));
+});
@@ -33,22 +33,30 @@ macro_rules! test {
 mod test_bad_config;
 mod test_bad_manifest_path;
 mod test_cargo;
+mod test_cargo_alias;
 mod test_cargo_bench;
 mod test_cargo_build_auth;
 mod test_cargo_build_lib;
+mod test_cargo_check;
 mod test_cargo_clean;
 mod test_cargo_compile;
 mod test_cargo_compile_custom_build;
 mod test_cargo_compile_git_deps;
 mod test_cargo_compile_path_deps;
 mod test_cargo_compile_plugins;
+mod test_cargo_config;
 mod test_cargo_cross_compile;
 mod test_cargo_doc;
+mod test_cargo_env_config;
 mod test_cargo_features;
 mod test_cargo_fetch;
 mod test_cargo_freshness;
 mod test_cargo_generate_lockfile;
+mod test_cargo_git_gc;
+mod test_cargo_info;
+mod test_cargo_init;
 mod test_cargo_install;
+mod test_cargo_net_config;
 mod test_cargo_new;
 mod test_cargo_package;
 mod test_cargo_profiles;